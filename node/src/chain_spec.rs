@@ -20,7 +20,7 @@ use cumulus_primitives_core::ParaId;
 use primitives::{AccountId, Balance, Signature};
 use runtime_eden::{
 	constants::NODL, AuraId, BalancesConfig, GenesisConfig, ParachainInfoConfig, SessionConfig, SessionKeys,
-	SystemConfig, TechnicalMembershipConfig, ValidatorsSetConfig, WASM_BINARY,
+	StakingConfig, SystemConfig, TechnicalMembershipConfig, WASM_BINARY,
 };
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
@@ -102,6 +102,7 @@ fn eden_testnet_genesis(
 	});
 
 	const ENDOWMENT: Balance = 10_000 * NODL;
+	const STASH: Balance = ENDOWMENT / 2;
 
 	GenesisConfig {
 		// Core
@@ -116,9 +117,9 @@ fn eden_testnet_genesis(
 		vesting: Default::default(),
 
 		// Consensus
-		validators_set: ValidatorsSetConfig {
-			members: collators.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
-			phantom: Default::default(),
+		staking: StakingConfig {
+			stakers: collators.iter().map(|x| (x.0.clone(), None, STASH)).collect::<Vec<_>>(),
+			invulnerables: collators.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
 		},
 		session: SessionConfig {
 			keys: collators
@@ -150,6 +151,7 @@ fn eden_testnet_genesis(
 
 		// Allocations
 		allocations_oracles: Default::default(),
+		emergency_guardians: Default::default(),
 	}
 }
 
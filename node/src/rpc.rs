@@ -20,7 +20,7 @@
 
 use std::sync::Arc;
 
-use primitives::{AccountId, Balance, Block, Index as Nonce};
+use primitives::{AccountId, Balance, Block, BlockNumber, Hash, Index as Nonce};
 
 use sc_client_api::AuxStore;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -54,10 +54,22 @@ where
 		+ 'static,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+	C::Api: pallet_allocations_rpc::AllocationsRuntimeApi<Block, AccountId, BlockNumber, Balance>,
+	C::Api: pallet_reserve_rpc::ReserveRuntimeApi<Block, AccountId, Balance, u32, u128, u32, u32>,
+	C::Api: pallet_amendments_rpc::AmendmentsRuntimeApi<
+		Block,
+		runtime_eden::OriginCaller,
+		Hash,
+		BlockNumber,
+		runtime_eden::Event,
+	>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 {
 	use frame_rpc_system::{FullSystem, SystemApi};
+	use pallet_allocations_rpc::{Allocations, AllocationsApi};
+	use pallet_amendments_rpc::{Amendments, AmendmentsApi};
+	use pallet_reserve_rpc::{Reserve, ReserveApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
@@ -72,7 +84,21 @@ where
 		pool,
 		deny_unsafe,
 	)));
-	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client)));
+	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
+		client.clone(),
+	)));
+	io.extend_with(AllocationsApi::to_delegate(Allocations::<_, _, Balance>::new(
+		client.clone(),
+	)));
+	io.extend_with(ReserveApi::to_delegate(Reserve::<_, _, Balance, u128>::new(
+		client.clone(),
+	)));
+	io.extend_with(AmendmentsApi::to_delegate(Amendments::<
+		_,
+		_,
+		runtime_eden::OriginCaller,
+		runtime_eden::Event,
+	>::new(client, deny_unsafe)));
 
 	io
 }
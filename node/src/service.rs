@@ -200,7 +200,16 @@ where
 		+ sp_block_builder::BlockBuilder<Block>
 		+ cumulus_primitives_core::CollectCollationInfo<Block>
 		+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
-		+ frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+		+ frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
+		+ pallet_allocations_rpc::AllocationsRuntimeApi<Block, AccountId, BlockNumber, Balance>
+		+ pallet_reserve_rpc::ReserveRuntimeApi<Block, AccountId, Balance, u32, u128, u32, u32>
+		+ pallet_amendments_rpc::AmendmentsRuntimeApi<
+			Block,
+			runtime_eden::OriginCaller,
+			Hash,
+			BlockNumber,
+			runtime_eden::Event,
+		>,
 	sc_client_api::StateBackendFor<TFullBackend<Block>, Block>: sp_api::StateBackend<BlakeTwo256>,
 	RB: Fn(
 			Arc<TFullClient<Block, RuntimeApi, WasmExecutor<HostFunctions>>>,
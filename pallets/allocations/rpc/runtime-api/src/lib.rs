@@ -0,0 +1,47 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Runtime API definition for the allocations pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_allocations::AllocationTotals;
+
+sp_api::decl_runtime_apis! {
+	pub trait AllocationsApi<AccountId, BlockNumber, Balance> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+	{
+		/// Coins that may have been allocated in total by now, according to the emission
+		/// curve and the maximum supply of the pallet.
+		fn emission_allowance() -> Balance;
+
+		/// Coins that can still be allocated right now.
+		fn emission_headroom() -> Balance;
+
+		/// Coins received by `who` as a grantee, over its lifetime and during `epoch`, or the
+		/// current accounting epoch if `None`.
+		fn recipient_totals(who: AccountId, epoch: Option<BlockNumber>) -> AllocationTotals<BlockNumber, Balance>;
+
+		/// Coins allocated by `oracle` over its lifetime and during `epoch`, or the current
+		/// accounting epoch if `None`.
+		fn oracle_totals(oracle: AccountId, epoch: Option<BlockNumber>) -> AllocationTotals<BlockNumber, Balance>;
+	}
+}
@@ -0,0 +1,167 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! RPC interface for the allocations pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_allocations_rpc_runtime_api::AllocationTotals;
+pub use pallet_allocations_rpc_runtime_api::AllocationsApi as AllocationsRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[rpc]
+pub trait AllocationsApi<BlockHash, AccountId, BlockNumber> {
+	/// Coins that may have been allocated in total by now.
+	#[rpc(name = "allocations_emissionAllowance")]
+	fn emission_allowance(&self, at: Option<BlockHash>) -> Result<NumberOrHex>;
+
+	/// Coins that can still be allocated right now.
+	#[rpc(name = "allocations_emissionHeadroom")]
+	fn emission_headroom(&self, at: Option<BlockHash>) -> Result<NumberOrHex>;
+
+	/// Coins received by `who` as a grantee, over its lifetime and during `epoch`, or the
+	/// current accounting epoch if omitted.
+	#[rpc(name = "allocations_recipientTotals")]
+	fn recipient_totals(
+		&self,
+		who: AccountId,
+		epoch: Option<BlockNumber>,
+		at: Option<BlockHash>,
+	) -> Result<AllocationTotals<BlockNumber, NumberOrHex>>;
+
+	/// Coins allocated by `oracle` over its lifetime and during `epoch`, or the current
+	/// accounting epoch if omitted.
+	#[rpc(name = "allocations_oracleTotals")]
+	fn oracle_totals(
+		&self,
+		oracle: AccountId,
+		epoch: Option<BlockNumber>,
+		at: Option<BlockHash>,
+	) -> Result<AllocationTotals<BlockNumber, NumberOrHex>>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// Allocations RPC methods, answered from the runtime API.
+pub struct Allocations<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> Allocations<C, Block, Balance> {
+	/// Create new `Allocations` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn into_number_or_hex<BlockNumber, Balance: Into<NumberOrHex>>(
+	totals: AllocationTotals<BlockNumber, Balance>,
+) -> AllocationTotals<BlockNumber, NumberOrHex> {
+	AllocationTotals {
+		lifetime: totals.lifetime.into(),
+		epoch: totals.epoch,
+		in_epoch: totals.in_epoch.into(),
+	}
+}
+
+impl<C, Block, AccountId, BlockNumber, Balance> AllocationsApi<<Block as BlockT>::Hash, AccountId, BlockNumber>
+	for Allocations<C, Block, Balance>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: AllocationsRuntimeApi<Block, AccountId, BlockNumber, Balance>,
+	AccountId: Codec,
+	BlockNumber: Codec,
+	Balance: Codec + Into<NumberOrHex> + Send + Sync + 'static,
+{
+	fn emission_allowance(&self, at: Option<<Block as BlockT>::Hash>) -> Result<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.emission_allowance(&at)
+			.map(Into::into)
+			.map_err(|e| runtime_error("Unable to query the emission allowance.", e))
+	}
+
+	fn emission_headroom(&self, at: Option<<Block as BlockT>::Hash>) -> Result<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.emission_headroom(&at)
+			.map(Into::into)
+			.map_err(|e| runtime_error("Unable to query the emission headroom.", e))
+	}
+
+	fn recipient_totals(
+		&self,
+		who: AccountId,
+		epoch: Option<BlockNumber>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<AllocationTotals<BlockNumber, NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.recipient_totals(&at, who, epoch)
+			.map(into_number_or_hex)
+			.map_err(|e| runtime_error("Unable to query the recipient totals.", e))
+	}
+
+	fn oracle_totals(
+		&self,
+		oracle: AccountId,
+		epoch: Option<BlockNumber>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<AllocationTotals<BlockNumber, NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.oracle_totals(&at, oracle, epoch)
+			.map(into_number_or_hex)
+			.map_err(|e| runtime_error("Unable to query the oracle totals.", e))
+	}
+}
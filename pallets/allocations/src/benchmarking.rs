@@ -26,10 +26,16 @@ use super::*;
 use crate::Pallet as Allocations;
 use frame_benchmarking::impl_benchmark_test_suite;
 use frame_benchmarking::{account, benchmarks};
+use frame_support::{
+	traits::{EnsureOrigin, UnfilteredDispatchable},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
+use sp_runtime::traits::Hash;
 use sp_std::prelude::*;
 
 const MAX_BYTES: u32 = 1_024;
+const MAX_CLAIMS: u32 = 1_000;
 const SEED: u32 = 0;
 
 pub struct BenchmarkConfig<T: Config> {
@@ -49,14 +55,177 @@ fn make_benchmark_config<T: Config>(u: u32) -> BenchmarkConfig<T> {
 	BenchmarkConfig { grantee, oracle }
 }
 
+fn make_program<T: Config>(oracles: Vec<T::AccountId>) -> AllocationProgramOf<T> {
+	AllocationProgram {
+		budget: T::MaximumCoinsEverAllocated::get(),
+		oracles: oracles.try_into().unwrap(),
+		fee: T::ProtocolFee::get(),
+		fee_receiver: T::ProtocolFeeReceiver::account_id(),
+	}
+}
+
 benchmarks! {
 	allocate {
 		let b in 1 .. MAX_BYTES;
 
 		let config = make_benchmark_config::<T>(0);
 
+		<Programs<T>>::insert(0, make_program::<T>(vec![config.oracle.clone()]));
+	}: _(RawOrigin::Signed(config.oracle.clone()), 0, config.grantee.clone(), 40000u32.into(), vec![1; b as usize])
+
+	allocate_vested {
+		let b in 1 .. MAX_BYTES;
+
+		let config = make_benchmark_config::<T>(0);
+
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+	}: _(RawOrigin::Signed(config.oracle.clone()), config.grantee.clone(), 40000u32.into(), 0u32.into(), 10u32.into(), 3, vec![1; b as usize])
+
+	batch_allocate {
+		let n in 1 .. T::MaxAllocs::get();
+		let b in 1 .. MAX_BYTES;
+
+		let config = make_benchmark_config::<T>(0);
+		let batch: Vec<(T::AccountId, BalanceOf<T>)> = (0..n)
+			.map(|i| (account("grantee", i, SEED), 40000u32.into()))
+			.collect();
+		let batch: BoundedVec<_, T::MaxAllocs> = batch.try_into().unwrap();
+
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+	}: _(RawOrigin::Signed(config.oracle.clone()), batch, vec![1; b as usize])
+
+	commit_rewards_root {
+		let config = make_benchmark_config::<T>(0);
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+	}: _(RawOrigin::Signed(config.oracle.clone()), T::Hash::default())
+
+	allocate_committed {
+		let d in 1 .. T::MaxProofDepth::get();
+
+		let config = make_benchmark_config::<T>(0);
+		let amount: BalanceOf<T> = 40000u32.into();
+		let proof: Vec<T::Hash> = (0..d).map(|i| T::Hashing::hash_of(&i)).collect();
+		let leaf = merkle::leaf_hash::<T::Hashing, _>(0, &(&config.grantee, amount));
+		let root = merkle::compute_root::<T::Hashing>(leaf, 0, &proof);
+		let proof: BoundedVec<_, T::MaxProofDepth> = proof.try_into().unwrap();
+
 		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
-	}: _(RawOrigin::Signed(config.oracle.clone()), config.grantee.clone(), 40000u32.into(), vec![1; b as usize])
+		<Commitments<T>>::insert(root, (config.oracle.clone(), T::BlockNumber::default()));
+	}: _(RawOrigin::Signed(config.oracle.clone()), root, 0, config.grantee.clone(), amount, proof)
+
+	publish_rewards_epoch {
+		let config = make_benchmark_config::<T>(0);
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+	}: _(RawOrigin::Signed(config.oracle.clone()), T::Hash::default(), 40000u32.into())
+
+	claim {
+		let d in 1 .. T::MaxProofDepth::get();
+
+		let config = make_benchmark_config::<T>(0);
+		let amount: BalanceOf<T> = 40000u32.into();
+		let proof: Vec<T::Hash> = (0..d).map(|i| T::Hashing::hash_of(&i)).collect();
+		let leaf = merkle::leaf_hash::<T::Hashing, _>(0, &(&config.grantee, amount));
+		let root = merkle::compute_root::<T::Hashing>(leaf, 0, &proof);
+		let proof: BoundedVec<_, T::MaxProofDepth> = proof.try_into().unwrap();
+
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+		Pallet::<T>::publish_rewards_epoch(RawOrigin::Signed(config.oracle.clone()).into(), root, amount)?;
+	}: _(RawOrigin::Signed(config.grantee.clone()), 0, 0, amount, proof)
+
+	expire_rewards_epoch {
+		let c in 0 .. MAX_CLAIMS;
+
+		let config = make_benchmark_config::<T>(0);
+
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+		Pallet::<T>::publish_rewards_epoch(RawOrigin::Signed(config.oracle.clone()).into(), T::Hash::default(), 40000u32.into())?;
+		for index in 0..c {
+			<ClaimedRewards<T>>::insert(0, index, ());
+		}
+		let expires_at = Pallet::<T>::rewards_epochs(0).unwrap().expires_at;
+		frame_system::Pallet::<T>::set_block_number(expires_at.saturating_add(1u32.into()));
+	}: _(RawOrigin::Signed(config.grantee.clone()), 0, c)
+
+	propose_batch {
+		let n in 1 .. T::MaxAllocs::get();
+		let b in 1 .. MAX_BYTES;
+
+		let config = make_benchmark_config::<T>(0);
+		let batch: Vec<(T::AccountId, BalanceOf<T>)> = (0..n)
+			.map(|i| (account("grantee", i, SEED), 40000u32.into()))
+			.collect();
+		let batch: BoundedVec<_, T::MaxAllocs> = batch.try_into().unwrap();
+
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+		<Quorum<T>>::put(2);
+	}: _(RawOrigin::Signed(config.oracle.clone()), batch, vec![1; b as usize])
+
+	approve_batch {
+		let n in 1 .. T::MaxAllocs::get();
+
+		let config = make_benchmark_config::<T>(0);
+		let approver = make_benchmark_config::<T>(1).oracle;
+		let batch: Vec<(T::AccountId, BalanceOf<T>)> = (0..n)
+			.map(|i| (account("grantee", i, SEED), 40000u32.into()))
+			.collect();
+		let batch: BoundedVec<_, T::MaxAllocs> = batch.try_into().unwrap();
+		let hash = T::Hashing::hash_of(&(&batch, &Vec::<u8>::new()));
+
+		Pallet::<T>::initialize_members(&[config.oracle.clone(), approver.clone()]);
+		<Quorum<T>>::put(2);
+		Pallet::<T>::propose_batch(RawOrigin::Signed(config.oracle.clone()).into(), batch, Vec::new())?;
+	}: _(RawOrigin::Signed(approver), hash)
+
+	remove_expired_batch {
+		let config = make_benchmark_config::<T>(0);
+		let batch: BoundedVec<_, T::MaxAllocs> = vec![(config.grantee.clone(), 40000u32.into())].try_into().unwrap();
+		let hash = T::Hashing::hash_of(&(&batch, &Vec::<u8>::new()));
+
+		Pallet::<T>::initialize_members(&[config.oracle.clone()]);
+		<Quorum<T>>::put(2);
+		Pallet::<T>::propose_batch(RawOrigin::Signed(config.oracle.clone()).into(), batch, Vec::new())?;
+		let expires_at = Pallet::<T>::pending_batches(hash).unwrap().expires_at;
+		frame_system::Pallet::<T>::set_block_number(expires_at.saturating_add(1u32.into()));
+	}: _(RawOrigin::Signed(config.grantee.clone()), hash)
+
+	set_quorum {
+		let call = Call::<T>::set_quorum { threshold: Some(2) };
+		let origin = T::QuorumOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	create_program {
+		let o in 1 .. T::MaxMembers::get();
+
+		let oracles = (0..o).map(|i| account("oracle", i, SEED)).collect();
+		let call = Call::<T>::create_program { program: make_program::<T>(oracles) };
+		let origin = T::ProgramOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	update_program {
+		let o in 1 .. T::MaxMembers::get();
+
+		let oracles = (0..o).map(|i| account("oracle", i, SEED)).collect();
+		<Programs<T>>::insert(0, make_program::<T>(Vec::new()));
+		let call = Call::<T>::update_program { program: 0, details: make_program::<T>(oracles) };
+		let origin = T::ProgramOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	remove_program {
+		<Programs<T>>::insert(0, make_program::<T>(Vec::new()));
+		<ProgramConsumed<T>>::insert(0, BalanceOf::<T>::from(40000u32));
+		let call = Call::<T>::remove_program { program: 0 };
+		let origin = T::ProgramOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	set_mint_limit {
+		let limit = MintLimit {
+			period: 10u32.into(),
+			max_per_period: 40000u32.into(),
+			max_per_oracle: 40000u32.into(),
+		};
+		let call = Call::<T>::set_mint_limit { limit: Some(limit) };
+		let origin = T::MintLimitOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
 
 	impl_benchmark_test_suite!(
 		Allocations,
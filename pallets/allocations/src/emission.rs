@@ -0,0 +1,97 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Emission curves bounding how many coins may have been allocated by a given block.
+
+use frame_support::traits::Get;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Bounded, SaturatedConversion, Saturating, Zero},
+	PerThing, Perbill,
+};
+use sp_std::marker::PhantomData;
+
+/// Defines how many coins may have been allocated in total by any given block.
+pub trait EmissionCurve<BlockNumber, Balance> {
+	/// Cumulative amount of coins that may have been allocated by block `at`.
+	fn cumulative_allowance(at: BlockNumber) -> Balance;
+}
+
+/// No curve, only `MaximumCoinsEverAllocated` applies.
+impl<BlockNumber, Balance: Bounded> EmissionCurve<BlockNumber, Balance> for () {
+	fn cumulative_allowance(_at: BlockNumber) -> Balance {
+		Balance::max_value()
+	}
+}
+
+/// Emits `Initial` coins over the first `Period` blocks after `Start`, and then `Decay`
+/// less than the previous period over every following period. Emissions are spread
+/// linearly within a period. A `Decay` of 50% gives a halving curve.
+///
+/// The full periods are summed as a geometric series, so the cost does not depend on how
+/// many periods elapsed. Unless `Decay` is zero, no more than `Initial / Decay` coins are
+/// ever emitted.
+pub struct DecayingEmission<Start, Period, Initial, Decay>(PhantomData<(Start, Period, Initial, Decay)>);
+
+impl<BlockNumber, Balance, Start, Period, Initial, Decay> EmissionCurve<BlockNumber, Balance>
+	for DecayingEmission<Start, Period, Initial, Decay>
+where
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+	Balance: AtLeast32BitUnsigned + Copy,
+	Start: Get<BlockNumber>,
+	Period: Get<BlockNumber>,
+	Initial: Get<Balance>,
+	Decay: Get<Perbill>,
+{
+	fn cumulative_allowance(at: BlockNumber) -> Balance {
+		let period = Period::get();
+		if at < Start::get() || period.is_zero() {
+			return Zero::zero();
+		}
+
+		let elapsed = at - Start::get();
+		let full_periods = elapsed / period;
+		let initial = Initial::get();
+		let decay = Decay::get();
+
+		// emissions of the period in progress, `Initial * (1 - Decay) ^ full_periods`
+		let per_period = pow(Perbill::one() - decay, full_periods) * initial;
+		// emissions of the full periods, `(Initial - per_period) / Decay`
+		let emitted = if decay.is_zero() {
+			initial.saturating_mul(full_periods.saturated_into::<u128>().saturated_into())
+		} else {
+			decay.saturating_reciprocal_mul_floor(initial.saturating_sub(per_period))
+		};
+
+		let partial = Perbill::from_rational(elapsed % period, period);
+		emitted.saturating_add(partial * per_period)
+	}
+}
+
+/// `base ^ exp`, by squaring.
+fn pow<N: AtLeast32BitUnsigned + Copy>(mut base: Perbill, mut exp: N) -> Perbill {
+	let two: N = 2u32.into();
+	let mut result = Perbill::one();
+	while !exp.is_zero() && !result.is_zero() {
+		if !(exp % two).is_zero() {
+			result = result * base;
+		}
+		base = base * base;
+		exp = exp / two;
+	}
+	result
+}
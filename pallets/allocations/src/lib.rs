@@ -21,27 +21,130 @@ mod benchmarking;
 #[cfg(test)]
 mod tests;
 
+use codec::{Decode, Encode};
+use emission::EmissionCurve;
 use frame_support::{
 	ensure,
 	traits::{tokens::ExistenceRequirement, ChangeMembers, Currency, Get, InitializeMembers},
-	transactional, PalletId,
+	transactional, BoundedVec, CloneNoBound, EqNoBound, PalletId, PartialEqNoBound, RuntimeDebugNoBound,
 };
 use frame_system::ensure_signed;
+use pallet_grants::{VestedTransfer, VestingSchedule};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_io::KillStorageResult;
 use sp_runtime::traits::AccountIdConversion;
-use sp_std::prelude::*;
-use support::WithAccountId;
+use sp_std::{fmt::Debug, prelude::*};
+use support::{OnProtocolFee, WithAccountId};
 
 use sp_runtime::{
-	traits::{CheckedAdd, Saturating, Zero},
-	DispatchResult, Perbill,
+	traits::{BlockNumberProvider, CheckedAdd, CheckedDiv, Hash, Saturating, Zero},
+	DispatchError, DispatchResult, Perbill, RuntimeDebug,
 };
 
+pub mod emission;
+pub mod merkle;
+pub mod migrations;
 pub mod weights;
 pub use weights::WeightInfo;
 
 pub use pallet::*;
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type ProgramId = u32;
+pub type AllocationProgramOf<T> =
+	AllocationProgram<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as Config>::MaxMembers>;
+pub type MintLimitOf<T> = MintLimit<<T as frame_system::Config>::BlockNumber, BalanceOf<T>>;
+pub type PendingBatchOf<T> = PendingBatch<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	<T as Config>::MaxAllocs,
+	<T as Config>::MaxMembers,
+>;
+pub type RewardsEpochOf<T> =
+	RewardsEpoch<<T as frame_system::Config>::Hash, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+/// Limits on how many coins can be allocated within a window of `period` blocks.
+///
+/// Windows are aligned on multiples of `period`, the budgets are reset whenever a
+/// new window starts.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct MintLimit<BlockNumber, Balance> {
+	/// Length of a window, in blocks
+	pub period: BlockNumber,
+	/// Maximum amount of coins allocated by all the oracles within a window
+	pub max_per_period: Balance,
+	/// Maximum amount of coins allocated by a single oracle within a window
+	pub max_per_oracle: Balance,
+}
+
+/// An incentive program minting from its own budget, with its own oracles and fees.
+///
+/// Only `allocate` is scoped to a program. The other ways of minting are reserved to the
+/// `Oracles` of the pallet, always pay `ProtocolFee` and are only bound by the pallet wide
+/// limits.
+#[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, scale_info::TypeInfo)]
+#[scale_info(skip_type_params(MaxOracles))]
+pub struct AllocationProgram<AccountId: Clone + Eq + Debug, Balance: Clone + Eq + Debug, MaxOracles: Get<u32>> {
+	/// Maximum amount of coins the program can ever allocate
+	pub budget: Balance,
+	/// Accounts allowed to allocate coins on behalf of the program, an oracle removed from
+	/// the pallet is removed from every program as well
+	pub oracles: BoundedVec<AccountId, MaxOracles>,
+	/// Share of every allocation sent to `fee_receiver`
+	pub fee: Perbill,
+	/// Account receiving the fees of the program
+	pub fee_receiver: AccountId,
+}
+
+/// Coins received by a recipient, or allocated by an oracle.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AllocationTotals<BlockNumber, Balance> {
+	/// Since the totals started being tracked
+	pub lifetime: Balance,
+	/// Accounting epoch `in_epoch` refers to
+	pub epoch: BlockNumber,
+	/// During `epoch`
+	pub in_epoch: Balance,
+}
+
+/// A batch of allocations waiting for enough oracles to approve it.
+#[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, scale_info::TypeInfo)]
+#[scale_info(skip_type_params(MaxAllocs, MaxApprovals))]
+pub struct PendingBatch<
+	AccountId: Clone + Eq + Debug,
+	Balance: Clone + Eq + Debug,
+	BlockNumber: Clone + Eq + Debug,
+	MaxAllocs: Get<u32>,
+	MaxApprovals: Get<u32>,
+> {
+	/// Allocations to be minted once the quorum is reached
+	pub batch: BoundedVec<(AccountId, Balance), MaxAllocs>,
+	/// Proof echoed in the allocation event
+	pub proof: Vec<u8>,
+	/// Oracle who proposed the batch, and on whose behalf it is minted
+	pub proposer: AccountId,
+	/// Oracles who approved the batch so far, including the proposer
+	pub approvals: BoundedVec<AccountId, MaxApprovals>,
+	/// Block after which the batch can no longer be approved
+	pub expires_at: BlockNumber,
+}
+
+/// Rewards published by an oracle that their recipients can claim until `expires_at`.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct RewardsEpoch<Hash, Balance, BlockNumber> {
+	/// Merkle root of the rewards, see `claim` for the leaves format
+	pub root: Hash,
+	/// Coins minted for this epoch
+	pub total: Balance,
+	/// Coins claimed so far
+	pub claimed: Balance,
+	/// Block after which the unclaimed coins can be sent back to the protocol
+	pub expires_at: BlockNumber,
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -58,21 +161,66 @@ pub mod pallet {
 
 		#[pallet::constant]
 		type ProtocolFee: Get<Perbill>;
-		type ProtocolFeeReceiver: WithAccountId<Self::AccountId>;
+		type ProtocolFeeReceiver: WithAccountId<Self::AccountId> + OnProtocolFee<BalanceOf<Self>>;
 
 		#[pallet::constant]
 		type MaximumCoinsEverAllocated: Get<BalanceOf<Self>>;
 
+		/// Maximum number of allocations that can be processed in a single batch
+		#[pallet::constant]
+		type MaxAllocs: Get<u32>;
+
+		/// Maximum number of oracles, should match the one of the membership pallet feeding
+		/// them. Also bounds the oracles of every program.
+		#[pallet::constant]
+		type MaxMembers: Get<u32>;
+
 		/// Runtime existential deposit
 		#[pallet::constant]
 		type ExistentialDeposit: Get<BalanceOf<Self>>;
 
+		/// Origin that can change the per window mint limits
+		type MintLimitOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Maximum depth of the merkle proofs used with committed reward batches
+		#[pallet::constant]
+		type MaxProofDepth: Get<u32>;
+
+		/// How long the rewards of an epoch can be claimed for
+		#[pallet::constant]
+		type ClaimPeriod: Get<Self::BlockNumber>;
+
+		/// Used to lock the grantee share of vested allocations
+		type Vesting: VestedTransfer<Self::AccountId, Self::BlockNumber, BalanceOf<Self>>;
+
+		/// Origin that can enable or disable the oracle quorum
+		type QuorumOrigin: EnsureOrigin<Self::Origin>;
+
+		/// How long a batch can collect approvals before it expires
+		#[pallet::constant]
+		type QuorumVotingPeriod: Get<Self::BlockNumber>;
+
+		/// Bounds how many coins may have been allocated by a given block, on top of
+		/// `MaximumCoinsEverAllocated`
+		type EmissionCurve: EmissionCurve<Self::BlockNumber, BalanceOf<Self>>;
+
+		/// Block number the emission curve is measured against, usually the relay chain one
+		type BlockNumberProvider: BlockNumberProvider<BlockNumber = Self::BlockNumber>;
+
+		/// Length of the epochs the recipient and oracle totals are tracked over
+		#[pallet::constant]
+		type AccountingEpoch: Get<Self::BlockNumber>;
+
+		/// Origin that can create, update and remove allocation programs
+		type ProgramOrigin: EnsureOrigin<Self::Origin>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(migrations::v1::STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(PhantomData<T>);
 
@@ -81,7 +229,8 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Can only be called by an oracle, trigger a coin creation and an event
+		/// Can only be called by an oracle of `program`, trigger a coin creation out of the
+		/// program budget and an event
 		#[pallet::weight(
 			<T as pallet::Config>::WeightInfo::allocate(proof.len() as u32)
 		)]
@@ -91,11 +240,15 @@ pub mod pallet {
 		#[transactional]
 		pub fn allocate(
 			origin: OriginFor<T>,
+			program: ProgramId,
 			to: T::AccountId,
 			amount: BalanceOf<T>,
 			proof: Vec<u8>,
 		) -> DispatchResultWithPostInfo {
-			Self::ensure_oracle(origin)?;
+			let oracle = ensure_signed(origin)?;
+			let details = Self::programs(program).ok_or(Error::<T>::UnknownProgram)?;
+			ensure!(details.oracles.contains(&oracle), Error::<T>::OracleAccessDenied);
+			Self::ensure_no_quorum()?;
 			ensure!(
 				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
 				Error::<T>::UnderShutdown
@@ -109,52 +262,569 @@ pub mod pallet {
 				return Ok(Pays::No.into());
 			}
 
-			let coins_already_allocated = Self::coins_consumed();
-			let coins_that_will_be_consumed = coins_already_allocated
-				.checked_add(&amount)
-				.ok_or("Overflow computing coins consumed")?;
+			Self::consume_program_budget(program, &details, amount)?;
+			let (amount_for_grantee, amount_for_protocol) =
+				Self::do_allocate(&oracle, &to, amount, details.fee, &details.fee_receiver)?;
+
+			Self::deposit_event(Event::NewAllocation(
+				program,
+				to,
+				amount_for_grantee,
+				amount_for_protocol,
+				proof,
+			));
+			Ok(Pays::No.into())
+		}
+
+		/// Can only be called by an oracle of the pallet, outside of any program, trigger a
+		/// coin creation whose grantee share vests over `period_count` periods of `period`
+		/// blocks after `start`, through `pallet_grants`. `start` and `period` are measured
+		/// with the block number provider of `pallet_grants`. Whatever can not be split evenly
+		/// across periods is sent liquid.
+		#[pallet::weight(
+			<T as pallet::Config>::WeightInfo::allocate_vested(proof.len() as u32)
+		)]
+		#[transactional]
+		pub fn allocate_vested(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+			start: T::BlockNumber,
+			period: T::BlockNumber,
+			period_count: u32,
+			proof: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let oracle = Self::ensure_oracle(origin)?;
+			Self::ensure_no_quorum()?;
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
+			ensure!(
+				amount >= T::ExistentialDeposit::get().saturating_mul(2u32.into()),
+				Error::<T>::DoesNotSatisfyExistentialDeposit,
+			);
+			ensure!(period_count > 0, Error::<T>::InvalidVestingSchedule);
+
+			let (amount_for_grantee, amount_for_protocol) = Self::mint_allocation(
+				&oracle,
+				amount,
+				T::ProtocolFee::get(),
+				&T::ProtocolFeeReceiver::account_id(),
+			)?;
+
+			let per_period = amount_for_grantee / period_count.into();
+			ensure!(!per_period.is_zero(), Error::<T>::InvalidVestingSchedule);
+			let liquid = amount_for_grantee.saturating_sub(per_period.saturating_mul(period_count.into()));
+			if !liquid.is_zero() {
+				T::Currency::transfer(
+					&T::PalletId::get().into_account(),
+					&to,
+					liquid,
+					// the vested transfer below needs what is left
+					ExistenceRequirement::KeepAlive,
+				)?;
+			}
+			T::Vesting::vested_transfer(
+				&T::PalletId::get().into_account(),
+				&to,
+				VestingSchedule {
+					start,
+					period,
+					period_count,
+					per_period,
+				},
+			)?;
+			Self::note_received(&to, amount_for_grantee);
+
+			Self::deposit_event(Event::NewVestedAllocation(
+				to,
+				amount_for_grantee,
+				amount_for_protocol,
+				proof,
+			));
+			Ok(Pays::No.into())
+		}
+
+		/// Can only be called by an oracle of the pallet, outside of any program, trigger a
+		/// coin creation for every entry of `batch` and a single summary event. Either all the
+		/// allocations succeed or none of them are applied.
+		#[pallet::weight(
+			<T as pallet::Config>::WeightInfo::batch_allocate(batch.len() as u32, proof.len() as u32)
+		)]
+		#[transactional]
+		pub fn batch_allocate(
+			origin: OriginFor<T>,
+			batch: BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxAllocs>,
+			proof: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let oracle = Self::ensure_oracle(origin)?;
+			Self::ensure_no_quorum()?;
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
+
+			let (total_for_grantees, total_for_protocol) = Self::do_batch_allocate(&oracle, &batch)?;
+
+			Self::deposit_event(Event::NewAllocationBatch(
+				batch.len() as u32,
+				total_for_grantees,
+				total_for_protocol,
+				proof,
+			));
+			Ok(Pays::No.into())
+		}
+
+		/// Can only be called by an oracle, anchor the merkle `root` of an off-chain batch of
+		/// rewards. Each reward can then be paid once through `allocate_committed`.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::commit_rewards_root())]
+		pub fn commit_rewards_root(origin: OriginFor<T>, root: T::Hash) -> DispatchResultWithPostInfo {
+			let oracle = Self::ensure_oracle(origin)?;
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
+			ensure!(
+				!<Commitments<T>>::contains_key(root),
+				Error::<T>::CommitmentAlreadyExists
+			);
+
+			<Commitments<T>>::insert(root, (oracle.clone(), frame_system::Pallet::<T>::block_number()));
+
+			Self::deposit_event(Event::RewardsRootCommitted(oracle, root));
+			Ok(Pays::No.into())
+		}
 
+		/// Can only be called by an oracle of the pallet, outside of any program, pay the
+		/// reward of `amount` to `to` found at position `index` of the committed batch `root`.
+		/// The leaf is the hash of `(index, to, amount)` and `proof` holds the hashes of its
+		/// siblings, from the bottom of the tree up to the root.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::allocate_committed(proof.len() as u32))]
+		#[transactional]
+		pub fn allocate_committed(
+			origin: OriginFor<T>,
+			root: T::Hash,
+			index: u32,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+			proof: BoundedVec<T::Hash, T::MaxProofDepth>,
+		) -> DispatchResultWithPostInfo {
+			let oracle = Self::ensure_oracle(origin)?;
+			Self::ensure_no_quorum()?;
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
 			ensure!(
-				coins_that_will_be_consumed <= T::MaximumCoinsEverAllocated::get(),
-				Error::<T>::TooManyCoinsToAllocate
+				amount >= T::ExistentialDeposit::get().saturating_mul(2u32.into()),
+				Error::<T>::DoesNotSatisfyExistentialDeposit,
+			);
+			ensure!(<Commitments<T>>::contains_key(root), Error::<T>::UnknownCommitment);
+			ensure!(
+				!<PaidLeaves<T>>::contains_key(root, index),
+				Error::<T>::RewardAlreadyPaid
 			);
 
-			// When using a Perbill type as T::ProtocolFee::get() returns the default way to go is to used the
-			// standard mathematic operands. The risk of {over, under}flow is void as this operation will
-			// effectively take a part of `amount` and thus always produce a lower number. (We use Perbill to
-			// represent percentages)
-			let amount_for_protocol = T::ProtocolFee::get() * amount;
-			let amount_for_grantee = amount.saturating_sub(amount_for_protocol);
+			let leaf = merkle::leaf_hash::<T::Hashing, _>(index, &(&to, amount));
+			ensure!(
+				merkle::verify_proof::<T::Hashing>(&root, leaf, index, &proof),
+				Error::<T>::InvalidMerkleProof
+			);
 
-			<CoinsConsumed<T>>::put(coins_that_will_be_consumed);
+			<PaidLeaves<T>>::insert(root, index, ());
+			let (amount_for_grantee, amount_for_protocol) = Self::do_allocate(
+				&oracle,
+				&to,
+				amount,
+				T::ProtocolFee::get(),
+				&T::ProtocolFeeReceiver::account_id(),
+			)?;
+
+			Self::deposit_event(Event::NewCommittedAllocation(
+				root,
+				index,
+				to,
+				amount_for_grantee,
+				amount_for_protocol,
+			));
+			Ok(Pays::No.into())
+		}
 
-			T::Currency::resolve_creating(&T::PalletId::get().into_account(), T::Currency::issue(amount));
+		/// Can only be called by an oracle of the pallet, outside of any program, mint `total`
+		/// coins for a new epoch of rewards committed to by the merkle `root`. Recipients then
+		/// have `ClaimPeriod` blocks to `claim` their rewards.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::publish_rewards_epoch())]
+		#[transactional]
+		pub fn publish_rewards_epoch(
+			origin: OriginFor<T>,
+			root: T::Hash,
+			total: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let oracle = Self::ensure_oracle(origin)?;
+			Self::ensure_no_quorum()?;
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
+			ensure!(
+				total >= T::ExistentialDeposit::get().saturating_mul(2u32.into()),
+				Error::<T>::DoesNotSatisfyExistentialDeposit,
+			);
+
+			// claims are paid with `KeepAlive` so that the dust of an epoch can not get the
+			// pot reaped along with the coins of the others, it is thus seeded with the
+			// existential deposit the first time it is used. The seed never leaves the pot, it
+			// is minted outside of `MaximumCoinsEverAllocated` and of the emission curve.
+			let seed = T::ExistentialDeposit::get().saturating_sub(T::Currency::free_balance(&Self::claims_account()));
+			Self::consume_coins(total)?;
+			Self::consume_window_budget(&oracle, total)?;
+			Self::note_allocated(&oracle, total);
+			T::Currency::resolve_creating(&Self::claims_account(), T::Currency::issue(total.saturating_add(seed)));
+
+			let epoch = Self::next_rewards_epoch();
+			let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(T::ClaimPeriod::get());
+			<RewardsEpochs<T>>::insert(
+				epoch,
+				RewardsEpoch {
+					root,
+					total,
+					claimed: Zero::zero(),
+					expires_at,
+				},
+			);
+			<NextRewardsEpoch<T>>::put(epoch.saturating_add(1));
+
+			Self::deposit_event(Event::RewardsEpochPublished(epoch, root, total, expires_at));
+			Ok(Pays::No.into())
+		}
+
+		/// Claim the reward of `amount` found at position `index` of the rewards of `epoch`.
+		/// The leaf is the hash of `(index, who, amount)` and `proof` holds the hashes of its
+		/// siblings, from the bottom of the tree up to the root. The protocol fee is taken
+		/// from the reward.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::claim(proof.len() as u32))]
+		#[transactional]
+		pub fn claim(
+			origin: OriginFor<T>,
+			epoch: u32,
+			index: u32,
+			amount: BalanceOf<T>,
+			proof: BoundedVec<T::Hash, T::MaxProofDepth>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
+
+			let mut rewards = Self::rewards_epochs(epoch).ok_or(Error::<T>::UnknownRewardsEpoch)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= rewards.expires_at,
+				Error::<T>::RewardsEpochExpired
+			);
+			ensure!(
+				!<ClaimedRewards<T>>::contains_key(epoch, index),
+				Error::<T>::RewardAlreadyPaid
+			);
+
+			let leaf = merkle::leaf_hash::<T::Hashing, _>(index, &(&who, amount));
+			ensure!(
+				merkle::verify_proof::<T::Hashing>(&rewards.root, leaf, index, &proof),
+				Error::<T>::InvalidMerkleProof
+			);
+
+			rewards.claimed = rewards
+				.claimed
+				.checked_add(&amount)
+				.ok_or("Overflow computing coins claimed")?;
+			ensure!(rewards.claimed <= rewards.total, Error::<T>::RewardsEpochExhausted);
+
+			let (amount_for_grantee, amount_for_protocol) = Self::split_allocation(amount, T::ProtocolFee::get());
 			T::Currency::transfer(
-				&T::PalletId::get().into_account(),
+				&Self::claims_account(),
 				&T::ProtocolFeeReceiver::account_id(),
 				amount_for_protocol,
-				// we use `KeepAlive` here because we want the guarantee that the funds left
-				// won't be considered dust, which would prevent us from sending the rest to
-				// the grantee.
 				ExistenceRequirement::KeepAlive,
 			)?;
+			T::ProtocolFeeReceiver::on_protocol_fee(amount_for_protocol);
 			T::Currency::transfer(
-				&T::PalletId::get().into_account(),
-				&to,
+				&Self::claims_account(),
+				&who,
 				amount_for_grantee,
-				ExistenceRequirement::AllowDeath,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			Self::note_received(&who, amount_for_grantee);
+
+			<ClaimedRewards<T>>::insert(epoch, index, ());
+			<RewardsEpochs<T>>::insert(epoch, rewards);
+
+			Self::deposit_event(Event::RewardClaimed(
+				epoch,
+				index,
+				who,
+				amount_for_grantee,
+				amount_for_protocol,
+			));
+			Ok(().into())
+		}
+
+		/// Close an epoch of rewards once its claim period is over, sending the coins that
+		/// were not claimed to the protocol fee receiver. Can be called by anyone.
+		///
+		/// At most `limit` of the claims recorded for the epoch are cleared per call, the
+		/// epoch is only closed by the call clearing the last of them.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::expire_rewards_epoch(*limit))]
+		#[transactional]
+		pub fn expire_rewards_epoch(origin: OriginFor<T>, epoch: u32, limit: u32) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let rewards = Self::rewards_epochs(epoch).ok_or(Error::<T>::UnknownRewardsEpoch)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > rewards.expires_at,
+				Error::<T>::RewardsEpochNotExpired
+			);
+
+			let cleared = match <ClaimedRewards<T>>::remove_prefix(epoch, Some(limit)) {
+				KillStorageResult::AllRemoved(cleared) => cleared,
+				KillStorageResult::SomeRemaining(cleared) => {
+					Self::deposit_event(Event::RewardsEpochClaimsCleared(epoch, cleared));
+					return Ok(Some(<T as pallet::Config>::WeightInfo::expire_rewards_epoch(cleared)).into());
+				}
+			};
+
+			let unclaimed = rewards.total.saturating_sub(rewards.claimed);
+			T::Currency::transfer(
+				&Self::claims_account(),
+				&T::ProtocolFeeReceiver::account_id(),
+				unclaimed,
+				ExistenceRequirement::KeepAlive,
 			)?;
+			T::ProtocolFeeReceiver::on_expired_rewards(unclaimed);
 
-			Self::deposit_event(Event::NewAllocation(to, amount_for_grantee, amount_for_protocol, proof));
+			<RewardsEpochs<T>>::remove(epoch);
+
+			Self::deposit_event(Event::RewardsEpochExpired(epoch, unclaimed));
+			Ok(Some(<T as pallet::Config>::WeightInfo::expire_rewards_epoch(cleared)).into())
+		}
+
+		/// Can only be called by an oracle of the pallet when the quorum is enabled, propose
+		/// `batch` to the other oracles, to be minted outside of any program. The proposal
+		/// counts as the first approval, the batch is identified by the hash of
+		/// `(batch, proof)`.
+		// a quorum of one mints the batch right away
+		#[pallet::weight(
+			<T as pallet::Config>::WeightInfo::propose_batch(batch.len() as u32, proof.len() as u32)
+				.saturating_add(<T as pallet::Config>::WeightInfo::approve_batch(batch.len() as u32))
+		)]
+		#[transactional]
+		pub fn propose_batch(
+			origin: OriginFor<T>,
+			batch: BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxAllocs>,
+			proof: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let oracle = Self::ensure_oracle(origin)?;
+			ensure!(Self::quorum().is_some(), Error::<T>::QuorumNotEnabled);
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
+			ensure!(!batch.is_empty(), Error::<T>::BatchEmpty);
+
+			let hash = T::Hashing::hash_of(&(&batch, &proof));
+			ensure!(
+				!<PendingBatches<T>>::contains_key(hash),
+				Error::<T>::BatchAlreadyPending
+			);
+
+			let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(T::QuorumVotingPeriod::get());
+			let pending = PendingBatch {
+				batch,
+				proof,
+				proposer: oracle.clone(),
+				approvals: vec![oracle.clone()]
+					.try_into()
+					.map_err(|_| Error::<T>::TooManyApprovals)?,
+				expires_at,
+			};
+
+			Self::deposit_event(Event::BatchProposed(hash, oracle, expires_at));
+			Self::try_execute_batch(hash, pending)?;
 			Ok(Pays::No.into())
 		}
+
+		/// Can only be called by an oracle, approve the pending batch identified by `hash`.
+		/// The batch is minted as soon as enough oracles approved it.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::approve_batch(T::MaxAllocs::get()))]
+		#[transactional]
+		pub fn approve_batch(origin: OriginFor<T>, hash: T::Hash) -> DispatchResultWithPostInfo {
+			let oracle = Self::ensure_oracle(origin)?;
+			ensure!(
+				!pallet_emergency_shutdown::Pallet::<T>::shutdown(),
+				Error::<T>::UnderShutdown
+			);
+
+			let mut pending = Self::pending_batches(hash).ok_or(Error::<T>::UnknownBatch)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= pending.expires_at,
+				Error::<T>::BatchExpired
+			);
+			ensure!(!pending.approvals.contains(&oracle), Error::<T>::AlreadyApproved);
+
+			// approvals of removed oracles no longer count, make room for the current ones
+			pending.approvals.retain(|approver| Self::is_oracle(approver.clone()));
+			pending
+				.approvals
+				.try_push(oracle.clone())
+				.map_err(|_| Error::<T>::TooManyApprovals)?;
+			Self::deposit_event(Event::BatchApproved(hash, oracle, pending.approvals.len() as u32));
+			Self::try_execute_batch(hash, pending)?;
+			Ok(Pays::No.into())
+		}
+
+		/// Remove a pending batch whose voting period is over. Can be called by anyone.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::remove_expired_batch())]
+		pub fn remove_expired_batch(origin: OriginFor<T>, hash: T::Hash) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let pending = Self::pending_batches(hash).ok_or(Error::<T>::UnknownBatch)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > pending.expires_at,
+				Error::<T>::BatchNotExpired
+			);
+
+			<PendingBatches<T>>::remove(hash);
+
+			Self::deposit_event(Event::BatchExpired(hash));
+			Ok(().into())
+		}
+
+		/// Require `threshold` distinct oracles to approve a batch before it is minted, or
+		/// let single oracles mint again if `None`. Pending batches are kept when the
+		/// threshold changes and are checked against the new one on their next approval.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_quorum())]
+		pub fn set_quorum(origin: OriginFor<T>, threshold: Option<u32>) -> DispatchResultWithPostInfo {
+			T::QuorumOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+
+			match threshold {
+				Some(threshold) => {
+					ensure!(threshold > 0, Error::<T>::InvalidQuorum);
+					<Quorum<T>>::put(threshold);
+				}
+				None => <Quorum<T>>::kill(),
+			}
+
+			Self::deposit_event(Event::QuorumSet(threshold));
+			Ok(().into())
+		}
+
+		/// Create a new allocation program, its id is reported in the `ProgramCreated` event.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::create_program(program.oracles.len() as u32))]
+		pub fn create_program(origin: OriginFor<T>, program: AllocationProgramOf<T>) -> DispatchResultWithPostInfo {
+			T::ProgramOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+
+			let id = Self::next_program_id();
+			<NextProgramId<T>>::put(id.checked_add(1).ok_or("Overflow computing program id")?);
+			<Programs<T>>::insert(id, program);
+
+			Self::deposit_event(Event::ProgramCreated(id));
+			Ok(().into())
+		}
+
+		/// Replace the settings of `program`. What the program already consumed is kept, so
+		/// lowering its budget under that amount stops it from allocating.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::update_program(details.oracles.len() as u32))]
+		pub fn update_program(
+			origin: OriginFor<T>,
+			program: ProgramId,
+			details: AllocationProgramOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::ProgramOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			ensure!(<Programs<T>>::contains_key(program), Error::<T>::UnknownProgram);
+
+			<Programs<T>>::insert(program, details);
+
+			Self::deposit_event(Event::ProgramUpdated(program));
+			Ok(().into())
+		}
+
+		/// Remove `program` along with its consumption.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::remove_program())]
+		pub fn remove_program(origin: OriginFor<T>, program: ProgramId) -> DispatchResultWithPostInfo {
+			T::ProgramOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			ensure!(<Programs<T>>::contains_key(program), Error::<T>::UnknownProgram);
+
+			<Programs<T>>::remove(program);
+			let consumed = <ProgramConsumed<T>>::take(program);
+
+			Self::deposit_event(Event::ProgramRemoved(program, consumed));
+			Ok(().into())
+		}
+
+		/// Set or clear the limits on how many coins can be allocated per window.
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_mint_limit())]
+		pub fn set_mint_limit(origin: OriginFor<T>, limit: Option<MintLimitOf<T>>) -> DispatchResultWithPostInfo {
+			T::MintLimitOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			match limit {
+				Some(ref limit) => {
+					ensure!(!limit.period.is_zero(), Error::<T>::InvalidMintLimit);
+					<MintLimitConfig<T>>::put(limit);
+				}
+				None => <MintLimitConfig<T>>::kill(),
+			}
+
+			Self::deposit_event(Event::MintLimitSet(limit));
+			Ok(().into())
+		}
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// An allocation was triggered \[who, value, fee, proof\]
-		NewAllocation(T::AccountId, BalanceOf<T>, BalanceOf<T>, Vec<u8>),
+		NewAllocation(ProgramId, T::AccountId, BalanceOf<T>, BalanceOf<T>, Vec<u8>),
+		/// A batch of allocations was triggered \[count, value, fee, proof\]
+		NewAllocationBatch(u32, BalanceOf<T>, BalanceOf<T>, Vec<u8>),
+		/// Mint limits were changed, or cleared if `None` \[limit\]
+		MintLimitSet(Option<MintLimitOf<T>>),
+		/// Budget left in the current window after an allocation \[oracle, left_in_window, left_for_oracle\]
+		MintBudgetLeft(T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// The merkle root of a batch of rewards was anchored \[oracle, root\]
+		RewardsRootCommitted(T::AccountId, T::Hash),
+		/// A reward from a committed batch was paid \[root, index, who, value, fee\]
+		NewCommittedAllocation(T::Hash, u32, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A new epoch of claimable rewards was published \[epoch, root, value, expires_at\]
+		RewardsEpochPublished(u32, T::Hash, BalanceOf<T>, T::BlockNumber),
+		/// A reward was claimed \[epoch, index, who, value, fee\]
+		RewardClaimed(u32, u32, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// An epoch of rewards expired, unclaimed coins went to the protocol \[epoch, unclaimed\]
+		RewardsEpochExpired(u32, BalanceOf<T>),
+		/// Some of the claims of an expired epoch were cleared, more are left \[epoch, cleared\]
+		RewardsEpochClaimsCleared(u32, u32),
+		/// A new allocation program was created \[program\]
+		ProgramCreated(ProgramId),
+		/// The settings of an allocation program were replaced \[program\]
+		ProgramUpdated(ProgramId),
+		/// An allocation program was removed \[program, consumed\]
+		ProgramRemoved(ProgramId, BalanceOf<T>),
+		/// Coins an allocation program can still allocate \[program, left\]
+		ProgramBudgetLeft(ProgramId, BalanceOf<T>),
+		/// An allocation was minted and its grantee share is vesting
+		/// \[who, value, fee, proof\]
+		NewVestedAllocation(T::AccountId, BalanceOf<T>, BalanceOf<T>, Vec<u8>),
+		/// The oracle quorum was changed, or disabled if `None` \[threshold\]
+		QuorumSet(Option<u32>),
+		/// A batch was proposed to the oracles \[hash, proposer, expires_at\]
+		BatchProposed(T::Hash, T::AccountId, T::BlockNumber),
+		/// A pending batch was approved by an oracle \[hash, oracle, approvals\]
+		BatchApproved(T::Hash, T::AccountId, u32),
+		/// A pending batch reached the quorum and was minted \[hash\]
+		BatchExecuted(T::Hash),
+		/// A pending batch expired before reaching the quorum \[hash\]
+		BatchExpired(T::Hash),
 	}
 
 	#[pallet::error]
@@ -167,6 +837,56 @@ pub mod pallet {
 		UnderShutdown,
 		/// Amount is too low and will conflict with the ExistentialDeposit parameter
 		DoesNotSatisfyExistentialDeposit,
+		/// Batch is empty, there is nothing to allocate
+		BatchEmpty,
+		/// The allocation would exceed what can be allocated in the current window
+		PeriodMintLimitExceeded,
+		/// The allocation would exceed what this oracle can allocate in the current window
+		OracleMintLimitExceeded,
+		/// Mint limits must have a non zero period
+		InvalidMintLimit,
+		/// This merkle root was already committed
+		CommitmentAlreadyExists,
+		/// No batch of rewards was committed with this merkle root
+		UnknownCommitment,
+		/// The merkle proof does not match the committed root
+		InvalidMerkleProof,
+		/// This reward was already paid
+		RewardAlreadyPaid,
+		/// No rewards were published for this epoch
+		UnknownRewardsEpoch,
+		/// The claim period of this epoch is over
+		RewardsEpochExpired,
+		/// The claim period of this epoch is not over yet
+		RewardsEpochNotExpired,
+		/// The rewards claimed would exceed what was minted for this epoch
+		RewardsEpochExhausted,
+		/// The allocation would exceed what the emission curve allows by now
+		EmissionCurveExceeded,
+		/// There is no allocation program with this id
+		UnknownProgram,
+		/// The allocation would exceed the budget of the program
+		ProgramBudgetExceeded,
+		/// The vesting schedule has no periods or less than one coin per period
+		InvalidVestingSchedule,
+		/// Allocations must go through a quorum of oracles
+		QuorumRequired,
+		/// The oracle quorum is not enabled
+		QuorumNotEnabled,
+		/// The quorum threshold must be greater than zero
+		InvalidQuorum,
+		/// This batch is already waiting for approvals
+		BatchAlreadyPending,
+		/// There is no pending batch with this hash
+		UnknownBatch,
+		/// This oracle already approved the batch
+		AlreadyApproved,
+		/// The batch has as many approvals as there can be oracles
+		TooManyApprovals,
+		/// The voting period of this batch is over
+		BatchExpired,
+		/// The voting period of this batch is not over yet
+		BatchNotExpired,
 	}
 
 	#[pallet::storage]
@@ -176,6 +896,94 @@ pub mod pallet {
 	#[pallet::storage]
 	#[pallet::getter(fn coins_consumed)]
 	pub type CoinsConsumed<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn mint_limit)]
+	pub type MintLimitConfig<T: Config> = StorageValue<_, MintLimitOf<T>, OptionQuery>;
+
+	/// Start of the window and coins allocated within it by all the oracles
+	#[pallet::storage]
+	#[pallet::getter(fn window_consumed)]
+	pub type WindowConsumed<T: Config> = StorageValue<_, (T::BlockNumber, BalanceOf<T>), ValueQuery>;
+
+	/// Start of the window and coins allocated within it by a given oracle
+	#[pallet::storage]
+	#[pallet::getter(fn oracle_window_consumed)]
+	pub type OracleWindowConsumed<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, BalanceOf<T>), ValueQuery>;
+
+	/// Merkle roots of the committed batches of rewards, along with the oracle who
+	/// committed them and when
+	#[pallet::storage]
+	#[pallet::getter(fn commitments)]
+	pub type Commitments<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, (T::AccountId, T::BlockNumber), OptionQuery>;
+
+	/// Rewards of the committed batches that were already paid, by merkle root and position
+	#[pallet::storage]
+	#[pallet::getter(fn paid_leaves)]
+	pub type PaidLeaves<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Hash, Twox64Concat, u32, (), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_rewards_epoch)]
+	pub type NextRewardsEpoch<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Epochs of rewards that can still be claimed
+	#[pallet::storage]
+	#[pallet::getter(fn rewards_epochs)]
+	pub type RewardsEpochs<T: Config> = StorageMap<_, Twox64Concat, u32, RewardsEpochOf<T>, OptionQuery>;
+
+	/// Rewards already claimed, by epoch and position
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_rewards)]
+	pub type ClaimedRewards<T: Config> = StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, u32, (), OptionQuery>;
+
+	/// Number of distinct oracles that need to approve a batch, if the quorum is enabled
+	#[pallet::storage]
+	#[pallet::getter(fn quorum)]
+	pub type Quorum<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// Batches waiting for the approval of the oracles, by hash
+	#[pallet::storage]
+	#[pallet::getter(fn pending_batches)]
+	pub type PendingBatches<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, PendingBatchOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_program_id)]
+	pub type NextProgramId<T: Config> = StorageValue<_, ProgramId, ValueQuery>;
+
+	/// Allocation programs, by id
+	#[pallet::storage]
+	#[pallet::getter(fn programs)]
+	pub type Programs<T: Config> = StorageMap<_, Twox64Concat, ProgramId, AllocationProgramOf<T>, OptionQuery>;
+
+	/// Coins allocated by each program so far
+	#[pallet::storage]
+	#[pallet::getter(fn program_consumed)]
+	pub type ProgramConsumed<T: Config> = StorageMap<_, Twox64Concat, ProgramId, BalanceOf<T>, ValueQuery>;
+
+	/// Coins received by each recipient since the totals started being tracked
+	#[pallet::storage]
+	#[pallet::getter(fn recipient_lifetime_totals)]
+	pub type RecipientLifetimeTotals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Coins received by each recipient, by accounting epoch
+	#[pallet::storage]
+	#[pallet::getter(fn recipient_epoch_totals)]
+	pub type RecipientEpochTotals<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Coins allocated by each oracle since the totals started being tracked
+	#[pallet::storage]
+	#[pallet::getter(fn oracle_lifetime_totals)]
+	pub type OracleLifetimeTotals<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Coins allocated by each oracle, by accounting epoch
+	#[pallet::storage]
+	#[pallet::getter(fn oracle_epoch_totals)]
+	pub type OracleEpochTotals<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
 }
 
 impl<T: Config> Pallet<T> {
@@ -183,16 +991,328 @@ impl<T: Config> Pallet<T> {
 		Self::oracles().contains(&who)
 	}
 
-	fn ensure_oracle(origin: T::Origin) -> DispatchResult {
+	/// Account holding the coins of the published rewards until they are claimed.
+	pub fn claims_account() -> T::AccountId {
+		T::PalletId::get().into_sub_account(b"claims")
+	}
+
+	fn ensure_oracle(origin: T::Origin) -> Result<T::AccountId, DispatchError> {
 		let sender = ensure_signed(origin)?;
-		ensure!(Self::is_oracle(sender), Error::<T>::OracleAccessDenied);
+		ensure!(Self::is_oracle(sender.clone()), Error::<T>::OracleAccessDenied);
+		Ok(sender)
+	}
+
+	/// Coins that may have been allocated in total by now, according to the emission curve
+	/// and `MaximumCoinsEverAllocated`.
+	pub fn emission_allowance() -> BalanceOf<T> {
+		let now = T::BlockNumberProvider::current_block_number();
+		T::EmissionCurve::cumulative_allowance(now).min(T::MaximumCoinsEverAllocated::get())
+	}
+
+	/// Coins that can still be allocated right now.
+	pub fn emission_headroom() -> BalanceOf<T> {
+		Self::emission_allowance().saturating_sub(Self::coins_consumed())
+	}
+
+	/// Accounting epoch the current block belongs to.
+	pub fn current_accounting_epoch() -> T::BlockNumber {
+		frame_system::Pallet::<T>::block_number()
+			.checked_div(&T::AccountingEpoch::get())
+			.unwrap_or_else(Zero::zero)
+	}
+
+	/// Coins received by `who` as a grantee, over its lifetime and during `epoch`, or the
+	/// current accounting epoch if `None`.
+	pub fn recipient_totals(
+		who: &T::AccountId,
+		epoch: Option<T::BlockNumber>,
+	) -> AllocationTotals<T::BlockNumber, BalanceOf<T>> {
+		let epoch = epoch.unwrap_or_else(Self::current_accounting_epoch);
+		AllocationTotals {
+			lifetime: Self::recipient_lifetime_totals(who),
+			epoch,
+			in_epoch: Self::recipient_epoch_totals(epoch, who),
+		}
+	}
+
+	/// Coins allocated by `oracle`, protocol fees included, over its lifetime and during
+	/// `epoch`, or the current accounting epoch if `None`.
+	pub fn oracle_totals(
+		oracle: &T::AccountId,
+		epoch: Option<T::BlockNumber>,
+	) -> AllocationTotals<T::BlockNumber, BalanceOf<T>> {
+		let epoch = epoch.unwrap_or_else(Self::current_accounting_epoch);
+		AllocationTotals {
+			lifetime: Self::oracle_lifetime_totals(oracle),
+			epoch,
+			in_epoch: Self::oracle_epoch_totals(epoch, oracle),
+		}
+	}
+
+	fn note_received(who: &T::AccountId, amount: BalanceOf<T>) {
+		<RecipientLifetimeTotals<T>>::mutate(who, |total| *total = total.saturating_add(amount));
+		<RecipientEpochTotals<T>>::mutate(Self::current_accounting_epoch(), who, |total| {
+			*total = total.saturating_add(amount)
+		});
+	}
+
+	fn note_allocated(oracle: &T::AccountId, amount: BalanceOf<T>) {
+		<OracleLifetimeTotals<T>>::mutate(oracle, |total| *total = total.saturating_add(amount));
+		<OracleEpochTotals<T>>::mutate(Self::current_accounting_epoch(), oracle, |total| {
+			*total = total.saturating_add(amount)
+		});
+	}
+
+	/// Coins `program` can still allocate, if it exists.
+	pub fn program_budget_left(program: ProgramId) -> Option<BalanceOf<T>> {
+		Self::programs(program).map(|details| details.budget.saturating_sub(Self::program_consumed(program)))
+	}
+
+	fn consume_program_budget(
+		program: ProgramId,
+		details: &AllocationProgramOf<T>,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		let consumed = Self::program_consumed(program)
+			.checked_add(&amount)
+			.ok_or("Overflow computing program coins consumed")?;
+		ensure!(consumed <= details.budget, Error::<T>::ProgramBudgetExceeded);
+		<ProgramConsumed<T>>::insert(program, consumed);
+
+		Self::deposit_event(Event::ProgramBudgetLeft(
+			program,
+			details.budget.saturating_sub(consumed),
+		));
+		Ok(())
+	}
+
+	fn ensure_no_quorum() -> DispatchResult {
+		ensure!(Self::quorum().is_none(), Error::<T>::QuorumRequired);
+		Ok(())
+	}
+
+	/// Mint `pending` if enough of the current oracles approved it, or store it otherwise.
+	fn try_execute_batch(hash: T::Hash, pending: PendingBatchOf<T>) -> DispatchResult {
+		let threshold = Self::quorum().ok_or(Error::<T>::QuorumNotEnabled)?;
+		// oracles may have been removed since they approved the batch
+		let approvals = pending
+			.approvals
+			.iter()
+			.filter(|oracle| Self::is_oracle((*oracle).clone()))
+			.count() as u32;
+
+		if approvals < threshold {
+			<PendingBatches<T>>::insert(hash, pending);
+			return Ok(());
+		}
+
+		<PendingBatches<T>>::remove(hash);
+		let (total_for_grantees, total_for_protocol) = Self::do_batch_allocate(&pending.proposer, &pending.batch)?;
+
+		Self::deposit_event(Event::BatchExecuted(hash));
+		Self::deposit_event(Event::NewAllocationBatch(
+			pending.batch.len() as u32,
+			total_for_grantees,
+			total_for_protocol,
+			pending.proof,
+		));
+		Ok(())
+	}
+
+	/// Book `amount` into `CoinsConsumed`, failing if this would exceed `MaximumCoinsEverAllocated`.
+	fn consume_coins(amount: BalanceOf<T>) -> DispatchResult {
+		let coins_already_allocated = Self::coins_consumed();
+		let coins_that_will_be_consumed = coins_already_allocated
+			.checked_add(&amount)
+			.ok_or("Overflow computing coins consumed")?;
+
+		ensure!(
+			coins_that_will_be_consumed <= T::MaximumCoinsEverAllocated::get(),
+			Error::<T>::TooManyCoinsToAllocate
+		);
+		ensure!(
+			coins_that_will_be_consumed <= Self::emission_allowance(),
+			Error::<T>::EmissionCurveExceeded
+		);
+
+		<CoinsConsumed<T>>::put(coins_that_will_be_consumed);
 		Ok(())
 	}
+
+	/// Book `amount` against the current mint window, for all the oracles and for `oracle`,
+	/// and report the budgets left in the window. This is a no-op if no limits are configured.
+	fn consume_window_budget(oracle: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		let limit = match Self::mint_limit() {
+			Some(limit) => limit,
+			None => return Ok(()),
+		};
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let window_start = now.saturating_sub(now % limit.period);
+		let consumed_in_window = |(start, consumed): (T::BlockNumber, BalanceOf<T>)| {
+			if start == window_start {
+				consumed
+			} else {
+				Zero::zero()
+			}
+		};
+
+		let total_consumed = consumed_in_window(Self::window_consumed())
+			.checked_add(&amount)
+			.ok_or("Overflow computing coins consumed")?;
+		ensure!(
+			total_consumed <= limit.max_per_period,
+			Error::<T>::PeriodMintLimitExceeded
+		);
+
+		let oracle_consumed = consumed_in_window(Self::oracle_window_consumed(oracle))
+			.checked_add(&amount)
+			.ok_or("Overflow computing coins consumed")?;
+		ensure!(
+			oracle_consumed <= limit.max_per_oracle,
+			Error::<T>::OracleMintLimitExceeded
+		);
+
+		<WindowConsumed<T>>::put((window_start, total_consumed));
+		<OracleWindowConsumed<T>>::insert(oracle, (window_start, oracle_consumed));
+
+		Self::deposit_event(Event::MintBudgetLeft(
+			oracle.clone(),
+			limit.max_per_period.saturating_sub(total_consumed),
+			limit.max_per_oracle.saturating_sub(oracle_consumed),
+		));
+		Ok(())
+	}
+
+	/// Mint `amount` on behalf of `oracle` and share it between `to` and the protocol. Returns
+	/// the amounts received by the grantee and the protocol.
+	fn do_allocate(
+		oracle: &T::AccountId,
+		to: &T::AccountId,
+		amount: BalanceOf<T>,
+		fee: Perbill,
+		fee_receiver: &T::AccountId,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		let (amount_for_grantee, amount_for_protocol) = Self::mint_allocation(oracle, amount, fee, fee_receiver)?;
+		T::Currency::transfer(
+			&T::PalletId::get().into_account(),
+			to,
+			amount_for_grantee,
+			ExistenceRequirement::AllowDeath,
+		)?;
+		Self::note_received(to, amount_for_grantee);
+
+		Ok((amount_for_grantee, amount_for_protocol))
+	}
+
+	/// Mint `amount` into the pallet account on behalf of `oracle` and send the protocol
+	/// share out, leaving the grantee share for the caller to pay.
+	fn mint_allocation(
+		oracle: &T::AccountId,
+		amount: BalanceOf<T>,
+		fee: Perbill,
+		fee_receiver: &T::AccountId,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		Self::consume_coins(amount)?;
+		Self::consume_window_budget(oracle, amount)?;
+		Self::note_allocated(oracle, amount);
+		let (amount_for_grantee, amount_for_protocol) = Self::split_allocation(amount, fee);
+
+		T::Currency::resolve_creating(&T::PalletId::get().into_account(), T::Currency::issue(amount));
+		T::Currency::transfer(
+			&T::PalletId::get().into_account(),
+			fee_receiver,
+			amount_for_protocol,
+			// we use `KeepAlive` here because we want the guarantee that the funds left
+			// won't be considered dust, which would prevent us from sending the rest to
+			// the grantee.
+			ExistenceRequirement::KeepAlive,
+		)?;
+		// programs may send their fees elsewhere, the protocol is only told about its own
+		if *fee_receiver == T::ProtocolFeeReceiver::account_id() {
+			T::ProtocolFeeReceiver::on_protocol_fee(amount_for_protocol);
+		}
+
+		Ok((amount_for_grantee, amount_for_protocol))
+	}
+
+	/// Mint the coins of every entry of `batch` on behalf of `oracle`, the protocol share of
+	/// the whole batch being sent at once. Returns the amounts received by the grantees and
+	/// the protocol.
+	fn do_batch_allocate(
+		oracle: &T::AccountId,
+		batch: &[(T::AccountId, BalanceOf<T>)],
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		ensure!(!batch.is_empty(), Error::<T>::BatchEmpty);
+
+		let min_allocation = T::ExistentialDeposit::get().saturating_mul(2u32.into());
+		let mut total: BalanceOf<T> = Zero::zero();
+		let mut total_for_grantees: BalanceOf<T> = Zero::zero();
+		let mut total_for_protocol: BalanceOf<T> = Zero::zero();
+		for (_, amount) in batch.iter() {
+			ensure!(*amount >= min_allocation, Error::<T>::DoesNotSatisfyExistentialDeposit);
+			total = total.checked_add(amount).ok_or("Overflow computing coins consumed")?;
+
+			let (amount_for_grantee, amount_for_protocol) = Self::split_allocation(*amount, T::ProtocolFee::get());
+			total_for_grantees = total_for_grantees.saturating_add(amount_for_grantee);
+			total_for_protocol = total_for_protocol.saturating_add(amount_for_protocol);
+		}
+
+		Self::consume_coins(total)?;
+		Self::consume_window_budget(oracle, total)?;
+		Self::note_allocated(oracle, total);
+
+		T::Currency::resolve_creating(&T::PalletId::get().into_account(), T::Currency::issue(total));
+		// the protocol share of the whole batch is sent at once, and first, for the same
+		// reasons as in `allocate`.
+		T::Currency::transfer(
+			&T::PalletId::get().into_account(),
+			&T::ProtocolFeeReceiver::account_id(),
+			total_for_protocol,
+			ExistenceRequirement::KeepAlive,
+		)?;
+		T::ProtocolFeeReceiver::on_protocol_fee(total_for_protocol);
+		for (to, amount) in batch.iter() {
+			let (amount_for_grantee, _) = Self::split_allocation(*amount, T::ProtocolFee::get());
+			T::Currency::transfer(
+				&T::PalletId::get().into_account(),
+				to,
+				amount_for_grantee,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::note_received(to, amount_for_grantee);
+		}
+
+		Ok((total_for_grantees, total_for_protocol))
+	}
+
+	/// Split `amount` into the part going to the grantee and the part going to the protocol.
+	fn split_allocation(amount: BalanceOf<T>, fee: Perbill) -> (BalanceOf<T>, BalanceOf<T>) {
+		// When using a Perbill type as `fee` the default way to go is to used the standard
+		// mathematic operands. The risk of {over, under}flow is void as this operation will
+		// effectively take a part of `amount` and thus always produce a lower number. (We use Perbill to
+		// represent percentages)
+		let amount_for_protocol = fee * amount;
+		let amount_for_grantee = amount.saturating_sub(amount_for_protocol);
+		(amount_for_grantee, amount_for_protocol)
+	}
 }
 
 impl<T: Config> ChangeMembers<T::AccountId> for Pallet<T> {
-	fn change_members_sorted(_incoming: &[T::AccountId], _outgoing: &[T::AccountId], new: &[T::AccountId]) {
+	fn change_members_sorted(_incoming: &[T::AccountId], outgoing: &[T::AccountId], new: &[T::AccountId]) {
 		<Oracles<T>>::put(new);
+
+		// Outgoing oracles must not keep allocating through the programs they were part of
+		if outgoing.is_empty() {
+			return;
+		}
+		let affected: Vec<(ProgramId, AllocationProgramOf<T>)> = <Programs<T>>::iter()
+			.filter(|(_, program)| program.oracles.iter().any(|oracle| outgoing.contains(oracle)))
+			.collect();
+		for (id, mut program) in affected {
+			program.oracles.retain(|oracle| !outgoing.contains(oracle));
+			<Programs<T>>::insert(id, program);
+		}
 	}
 }
 
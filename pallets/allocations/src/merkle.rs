@@ -0,0 +1,48 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Binary merkle trees used to commit to off-chain reward batches.
+//!
+//! Leaves are hashed from their position in the tree along with their content, and
+//! nodes are hashed from the concatenation of their left and right children. A
+//! proof is the list of sibling hashes from the leaf up to the root.
+
+use codec::Encode;
+use sp_runtime::traits::Hash;
+
+/// Compute the hash of the leaf at position `index` holding `data`.
+pub fn leaf_hash<H: Hash, D: Encode>(index: u32, data: &D) -> H::Output {
+	H::hash_of(&(index, data))
+}
+
+/// Compute the root of the tree `leaf` belongs to, given its position `index` and the
+/// hashes of its siblings.
+pub fn compute_root<H: Hash>(leaf: H::Output, index: u32, proof: &[H::Output]) -> H::Output {
+	proof.iter().enumerate().fold(leaf, |node, (depth, sibling)| {
+		if index.checked_shr(depth as u32).unwrap_or(0) & 1 == 0 {
+			H::hash_of(&(node, sibling))
+		} else {
+			H::hash_of(&(sibling, node))
+		}
+	})
+}
+
+/// Check that `leaf` is included at position `index` in the tree with the given `root`.
+pub fn verify_proof<H: Hash>(root: &H::Output, leaf: H::Output, index: u32, proof: &[H::Output]) -> bool {
+	compute_root::<H>(leaf, index, proof) == *root
+}
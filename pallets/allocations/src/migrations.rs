@@ -0,0 +1,109 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+pub mod v1 {
+	use crate::{AllocationProgram, BalanceOf, Config, NextProgramId, Oracles, Pallet, Programs};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Get, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Saturating;
+	use support::WithAccountId;
+
+	/// The current storage version.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	/// `allocate` became scoped to a program. Creates a program for the oracles allocating
+	/// until now, with the protocol fee and whatever is left of `MaximumCoinsEverAllocated`
+	/// as its budget, so that they can keep allocating after the upgrade.
+	pub struct CreateDefaultProgram<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for CreateDefaultProgram<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if StorageVersion::get::<Pallet<T>>() >= STORAGE_VERSION {
+				return weight;
+			}
+
+			let mut oracles = <Oracles<T>>::get();
+			weight = weight.saturating_add(T::DbWeight::get().reads(1));
+			if !oracles.is_empty() {
+				// The membership pallet already bounds the oracles to `MaxMembers`
+				oracles.truncate(T::MaxMembers::get() as usize);
+				let oracles: BoundedVec<_, T::MaxMembers> = oracles.try_into().unwrap_or_default();
+				let id = <NextProgramId<T>>::get();
+				let budget: BalanceOf<T> =
+					T::MaximumCoinsEverAllocated::get().saturating_sub(Pallet::<T>::coins_consumed());
+				<Programs<T>>::insert(
+					id,
+					AllocationProgram {
+						budget,
+						oracles,
+						fee: T::ProtocolFee::get(),
+						fee_receiver: T::ProtocolFeeReceiver::account_id(),
+					},
+				);
+				<NextProgramId<T>>::put(id.saturating_add(1));
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+			}
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+			weight.saturating_add(T::DbWeight::get().writes(1))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+			// Already migrated, nothing left to check against.
+			if StorageVersion::get::<Pallet<T>>() >= STORAGE_VERSION {
+				return Ok(());
+			}
+
+			Self::set_temp_storage(<Oracles<T>>::get(), "oracles");
+			Self::set_temp_storage(<NextProgramId<T>>::get(), "program_id");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+			use sp_std::vec::Vec;
+
+			if StorageVersion::get::<Pallet<T>>() != STORAGE_VERSION {
+				return Err("Allocations storage version not bumped!");
+			}
+
+			let oracles: Vec<T::AccountId> = match Self::get_temp_storage("oracles") {
+				Some(oracles) => oracles,
+				// Nothing was migrated during this upgrade.
+				None => return Ok(()),
+			};
+			if oracles.is_empty() {
+				return Ok(());
+			}
+
+			let id: crate::ProgramId =
+				Self::get_temp_storage("program_id").ok_or("Pre upgrade program id not found!")?;
+			match <Programs<T>>::get(id) {
+				Some(program) if program.oracles[..] == oracles[..] => Ok(()),
+				_ => Err("Default allocation program not created for the oracles!"),
+			}
+		}
+	}
+}
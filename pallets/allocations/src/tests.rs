@@ -19,13 +19,20 @@
 #![cfg(test)]
 
 use super::*;
-use crate::{self as pallet_allocations};
-use frame_support::{assert_noop, assert_ok, ord_parameter_types, parameter_types, weights::Pays, PalletId};
+use crate::{
+	self as pallet_allocations,
+	emission::{DecayingEmission, EmissionCurve},
+};
+use frame_support::{
+	assert_noop, assert_ok, bounded_vec, ord_parameter_types, parameter_types, traits::OnRuntimeUpgrade, weights::Pays,
+	BoundedVec, PalletId,
+};
 use frame_system::EnsureSignedBy;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, Hash, IdentityLookup},
+	DispatchError::BadOrigin,
 	Perbill,
 };
 
@@ -41,6 +48,7 @@ frame_support::construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Config<T>, Storage, Event<T>},
 		EmergencyShutdown: pallet_emergency_shutdown::{Pallet, Call, Storage, Event<T>},
+		Vesting: pallet_grants::{Pallet, Call, Storage, Event<T>},
 		Allocations: pallet_allocations::{Pallet, Call, Storage, Event<T>},
 	}
 );
@@ -91,11 +99,27 @@ impl pallet_balances::Config for Test {
 }
 ord_parameter_types! {
 	pub const ShutdownAdmin: u64 = 21;
+	pub const LimitAdmin: u64 = 22;
+	pub const QuorumAdmin: u64 = 23;
+	pub const ProgramAdmin: u64 = 24;
 }
 impl pallet_emergency_shutdown::Config for Test {
 	type Event = ();
 	type ShutdownOrigin = EnsureSignedBy<ShutdownAdmin, u64>;
+	type GuardianOrigin = EnsureSignedBy<ShutdownAdmin, u64>;
+	type GuardianScopes = frame_support::traits::Everything;
+	type MaxReasonLength = frame_support::traits::ConstU32<64>;
+	type MaxNameLength = frame_support::traits::ConstU32<64>;
+	type PausablePallets = frame_support::traits::Everything;
+	type WeightInfo = ();
+}
+impl pallet_grants::Config for Test {
+	type Event = ();
+	type Currency = pallet_balances::Pallet<Self>;
+	type CancelOrigin = EnsureSignedBy<ShutdownAdmin, u64>;
+	type ForceOrigin = EnsureSignedBy<ShutdownAdmin, u64>;
 	type WeightInfo = ();
+	type BlockNumberProvider = frame_system::Pallet<Test>;
 }
 
 parameter_types! {
@@ -104,14 +128,50 @@ parameter_types! {
 	pub const Grantee: u64 = 2;
 	pub const Receiver: u64 = 3;
 	pub const CoinsLimit: u64 = 1_000_000;
+	pub const MaxAllocs: u32 = 10;
+	pub const MaxMembers: u32 = 10;
+	pub const MaxProofDepth: u32 = 8;
+	pub const ClaimPeriod: u64 = 100;
+	pub const QuorumVotingPeriod: u64 = 10;
+	pub const AccountingEpoch: u64 = 50;
 	pub const Fee: Perbill = Perbill::from_percent(10);
 	pub const AllocPalletId: PalletId = PalletId(*b"py/alloc");
 }
+parameter_types! {
+	pub static CurveEnabled: bool = false;
+	pub const EmissionStart: u64 = 10;
+	pub const EmissionPeriod: u64 = 10;
+	pub const EmissionInitial: u64 = 1_000;
+	pub const Halving: Perbill = Perbill::from_percent(50);
+}
+pub struct TestCurve;
+impl EmissionCurve<u64, u64> for TestCurve {
+	fn cumulative_allowance(at: u64) -> u64 {
+		if CurveEnabled::get() {
+			DecayingEmission::<EmissionStart, EmissionPeriod, EmissionInitial, Halving>::cumulative_allowance(at)
+		} else {
+			<() as EmissionCurve<u64, u64>>::cumulative_allowance(at)
+		}
+	}
+}
 impl WithAccountId<u64> for Receiver {
 	fn account_id() -> u64 {
 		Receiver::get()
 	}
 }
+parameter_types! {
+	pub static ProtocolFeesNotified: u64 = 0;
+	pub static ExpiredRewardsNotified: u64 = 0;
+}
+impl OnProtocolFee<u64> for Receiver {
+	fn on_protocol_fee(amount: u64) {
+		ProtocolFeesNotified::mutate(|notified| *notified += amount);
+	}
+
+	fn on_expired_rewards(amount: u64) {
+		ExpiredRewardsNotified::mutate(|notified| *notified += amount);
+	}
+}
 impl Config for Test {
 	type Event = ();
 	type Currency = pallet_balances::Pallet<Self>;
@@ -119,7 +179,19 @@ impl Config for Test {
 	type ProtocolFee = Fee;
 	type ProtocolFeeReceiver = Receiver;
 	type MaximumCoinsEverAllocated = CoinsLimit;
+	type MaxAllocs = MaxAllocs;
+	type MaxMembers = MaxMembers;
 	type ExistentialDeposit = <Test as pallet_balances::Config>::ExistentialDeposit;
+	type MintLimitOrigin = EnsureSignedBy<LimitAdmin, u64>;
+	type MaxProofDepth = MaxProofDepth;
+	type ClaimPeriod = ClaimPeriod;
+	type Vesting = pallet_grants::Pallet<Self>;
+	type QuorumOrigin = EnsureSignedBy<QuorumAdmin, u64>;
+	type QuorumVotingPeriod = QuorumVotingPeriod;
+	type EmissionCurve = TestCurve;
+	type BlockNumberProvider = System;
+	type AccountingEpoch = AccountingEpoch;
+	type ProgramOrigin = EnsureSignedBy<ProgramAdmin, u64>;
 	type WeightInfo = ();
 }
 type Errors = Error<Test>;
@@ -133,11 +205,25 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		.into()
 }
 
+fn new_program(oracles: &[u64]) {
+	assert_ok!(Allocations::create_program(
+		Origin::signed(ProgramAdmin::get()),
+		AllocationProgram {
+			budget: u64::MAX,
+			oracles: oracles.to_vec().try_into().unwrap(),
+			fee: Fee::get(),
+			fee_receiver: Receiver::get(),
+		}
+	));
+}
+
 #[test]
 fn non_oracle_can_not_trigger_allocation() {
 	new_test_ext().execute_with(|| {
+		new_program(&[Oracle::get()]);
+
 		assert_noop!(
-			Allocations::allocate(Origin::signed(Hacker::get()), Grantee::get(), 50, Vec::new(),),
+			Allocations::allocate(Origin::signed(Hacker::get()), 0, Grantee::get(), 50, Vec::new(),),
 			Errors::OracleAccessDenied
 		);
 	})
@@ -147,8 +233,9 @@ fn non_oracle_can_not_trigger_allocation() {
 fn oracle_does_not_pay_fees() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 		assert_eq!(
-			Allocations::allocate(Origin::signed(Oracle::get()), Grantee::get(), 50, Vec::new(),),
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 50, Vec::new(),),
 			Ok(Pays::No.into())
 		);
 	})
@@ -158,10 +245,12 @@ fn oracle_does_not_pay_fees() {
 fn oracle_triggers_allocation() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 		assert_eq!(Allocations::is_oracle(Oracle::get()), true);
 
 		assert_ok!(Allocations::allocate(
 			Origin::signed(Oracle::get()),
+			0,
 			Grantee::get(),
 			50,
 			Vec::new(),
@@ -173,9 +262,10 @@ fn oracle_triggers_allocation() {
 fn hacker_triggers_zero_allocation() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 
 		assert_noop!(
-			Allocations::allocate(Origin::signed(Hacker::get()), Grantee::get(), 0, Vec::new(),),
+			Allocations::allocate(Origin::signed(Hacker::get()), 0, Grantee::get(), 0, Vec::new(),),
 			Errors::OracleAccessDenied
 		);
 	})
@@ -185,11 +275,15 @@ fn hacker_triggers_zero_allocation() {
 fn oracle_triggers_zero_allocation_under_emergency_shutdown() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 
-		assert_ok!(EmergencyShutdown::toggle(Origin::signed(ShutdownAdmin::get())));
+		assert_ok!(EmergencyShutdown::toggle(
+			Origin::signed(ShutdownAdmin::get()),
+			Some(pallet_emergency_shutdown::ShutdownReason::Maintenance)
+		));
 
 		assert_noop!(
-			Allocations::allocate(Origin::signed(Oracle::get()), Grantee::get(), 0, Vec::new(),),
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 0, Vec::new(),),
 			Errors::UnderShutdown
 		);
 	})
@@ -199,10 +293,12 @@ fn oracle_triggers_zero_allocation_under_emergency_shutdown() {
 fn allocate_the_right_amount_of_coins_to_everyone() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 
 		assert_eq!(Allocations::coins_consumed(), 0);
 		assert_ok!(Allocations::allocate(
 			Origin::signed(Oracle::get()),
+			0,
 			Grantee::get(),
 			50,
 			Vec::new(),
@@ -218,20 +314,21 @@ fn allocate_the_right_amount_of_coins_to_everyone() {
 fn error_if_too_small_for_existential_deposit() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 
 		// grant smaller than deposit
 		assert_noop!(
-			Allocations::allocate(Origin::signed(Oracle::get()), Grantee::get(), 1, Vec::new()),
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 1, Vec::new()),
 			Errors::DoesNotSatisfyExistentialDeposit,
 		);
 
 		// grant satisfy deposit but would not be enough for both protocol and user
 		assert_noop!(
-			Allocations::allocate(Origin::signed(Oracle::get()), Grantee::get(), 2, Vec::new()),
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 2, Vec::new()),
 			Errors::DoesNotSatisfyExistentialDeposit,
 		);
 		assert_noop!(
-			Allocations::allocate(Origin::signed(Oracle::get()), Grantee::get(), 3, Vec::new()),
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 3, Vec::new()),
 			Errors::DoesNotSatisfyExistentialDeposit,
 		);
 
@@ -245,12 +342,14 @@ fn error_if_too_small_for_existential_deposit() {
 fn do_not_error_if_too_small_for_existential_deposit_but_balance_ok() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 
 		Balances::make_free_balance_be(&Grantee::get(), ExistentialDeposit::get());
 		Balances::make_free_balance_be(&Receiver::get(), ExistentialDeposit::get());
 
 		assert_ok!(Allocations::allocate(
 			Origin::signed(Oracle::get()),
+			0,
 			Grantee::get(),
 			10,
 			Vec::new()
@@ -272,10 +371,12 @@ fn do_not_error_if_too_small_for_existential_deposit_but_balance_ok() {
 fn can_not_allocate_more_coins_than_max() {
 	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
 
 		assert_noop!(
 			Allocations::allocate(
 				Origin::signed(Oracle::get()),
+				0,
 				Grantee::get(),
 				CoinsLimit::get() + 1,
 				Vec::new(),
@@ -288,12 +389,1066 @@ fn can_not_allocate_more_coins_than_max() {
 #[test]
 fn emergency_shutdown() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(EmergencyShutdown::toggle(Origin::signed(ShutdownAdmin::get())));
+		assert_ok!(EmergencyShutdown::toggle(
+			Origin::signed(ShutdownAdmin::get()),
+			Some(pallet_emergency_shutdown::ShutdownReason::Maintenance)
+		));
+		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
+
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 42, Vec::new(),),
+			Errors::UnderShutdown
+		);
+	})
+}
+
+#[test]
+fn non_oracle_can_not_trigger_batch_allocation() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Allocations::batch_allocate(
+				Origin::signed(Hacker::get()),
+				bounded_vec![(Grantee::get(), 50)],
+				Vec::new()
+			),
+			Errors::OracleAccessDenied
+		);
+	})
+}
+
+#[test]
+fn batch_allocate_the_right_amount_of_coins_to_everyone() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		assert_eq!(
+			Allocations::batch_allocate(
+				Origin::signed(Oracle::get()),
+				bounded_vec![(Grantee::get(), 50), (Hacker::get(), 100)],
+				Vec::new(),
+			),
+			Ok(Pays::No.into())
+		);
+
+		assert_eq!(Balances::free_balance(Grantee::get()), 45);
+		assert_eq!(Balances::free_balance(Hacker::get()), 90);
+		assert_eq!(Balances::free_balance(Receiver::get()), 15);
+		assert_eq!(Allocations::coins_consumed(), 150);
+	})
+}
+
+#[test]
+fn batch_allocate_fails_if_empty() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		assert_noop!(
+			Allocations::batch_allocate(Origin::signed(Oracle::get()), bounded_vec![], Vec::new()),
+			Errors::BatchEmpty
+		);
+	})
+}
+
+#[test]
+fn batch_allocate_is_atomic() {
+	new_test_ext().execute_with(|| {
 		Allocations::initialize_members(&[Oracle::get()]);
 
+		// one entry is too small for the existential deposit
 		assert_noop!(
-			Allocations::allocate(Origin::signed(Oracle::get()), Grantee::get(), 42, Vec::new(),),
+			Allocations::batch_allocate(
+				Origin::signed(Oracle::get()),
+				bounded_vec![(Grantee::get(), 50), (Hacker::get(), 3)],
+				Vec::new(),
+			),
+			Errors::DoesNotSatisfyExistentialDeposit
+		);
+
+		// the batch as a whole exceeds the maximum
+		assert_noop!(
+			Allocations::batch_allocate(
+				Origin::signed(Oracle::get()),
+				bounded_vec![(Grantee::get(), 50), (Hacker::get(), CoinsLimit::get())],
+				Vec::new(),
+			),
+			Errors::TooManyCoinsToAllocate
+		);
+
+		assert_eq!(Balances::free_balance(Grantee::get()), 0);
+		assert_eq!(Balances::free_balance(Receiver::get()), 0);
+		assert_eq!(Allocations::coins_consumed(), 0);
+	})
+}
+
+#[test]
+fn batch_allocate_under_emergency_shutdown() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(EmergencyShutdown::toggle(
+			Origin::signed(ShutdownAdmin::get()),
+			Some(pallet_emergency_shutdown::ShutdownReason::Maintenance)
+		));
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		assert_noop!(
+			Allocations::batch_allocate(
+				Origin::signed(Oracle::get()),
+				bounded_vec![(Grantee::get(), 50)],
+				Vec::new()
+			),
 			Errors::UnderShutdown
 		);
 	})
 }
+
+fn limit(period: u64, max_per_period: u64, max_per_oracle: u64) -> MintLimitOf<Test> {
+	MintLimit {
+		period,
+		max_per_period,
+		max_per_oracle,
+	}
+}
+
+#[test]
+fn non_admin_can_not_set_mint_limit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Allocations::set_mint_limit(Origin::signed(Hacker::get()), Some(limit(10, 100, 100))),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn can_not_set_mint_limit_with_zero_period() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Allocations::set_mint_limit(Origin::signed(LimitAdmin::get()), Some(limit(0, 100, 100))),
+			Errors::InvalidMintLimit
+		);
+	})
+}
+
+#[test]
+fn mint_limit_per_period_is_enforced() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get(), Hacker::get()]);
+		new_program(&[Oracle::get(), Hacker::get()]);
+		assert_ok!(Allocations::set_mint_limit(
+			Origin::signed(LimitAdmin::get()),
+			Some(limit(10, 100, 60))
+		));
+
+		System::set_block_number(12);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			60,
+			Vec::new(),
+		));
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 10, Vec::new()),
+			Errors::OracleMintLimitExceeded
+		);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Hacker::get()),
+			0,
+			Grantee::get(),
+			40,
+			Vec::new(),
+		));
+		assert_noop!(
+			Allocations::batch_allocate(
+				Origin::signed(Hacker::get()),
+				bounded_vec![(Grantee::get(), 10)],
+				Vec::new()
+			),
+			Errors::PeriodMintLimitExceeded
+		);
+		assert_eq!(Allocations::window_consumed(), (10, 100));
+		assert_eq!(Allocations::oracle_window_consumed(Oracle::get()), (10, 60));
+
+		// budgets are reset with the next window
+		System::set_block_number(20);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			60,
+			Vec::new(),
+		));
+		assert_eq!(Allocations::window_consumed(), (20, 60));
+		assert_eq!(Allocations::coins_consumed(), 160);
+	})
+}
+
+#[test]
+fn clearing_mint_limit_lifts_restrictions() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
+		assert_ok!(Allocations::set_mint_limit(
+			Origin::signed(LimitAdmin::get()),
+			Some(limit(10, 10, 10))
+		));
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 50, Vec::new()),
+			Errors::PeriodMintLimitExceeded
+		);
+
+		assert_ok!(Allocations::set_mint_limit(Origin::signed(LimitAdmin::get()), None));
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			50,
+			Vec::new(),
+		));
+	})
+}
+
+/// Build a tree of four rewards, returning its root and the leaves hashes.
+fn make_rewards_tree(rewards: &[(u64, u64); 4]) -> (H256, Vec<H256>) {
+	let leaves: Vec<H256> = rewards
+		.iter()
+		.enumerate()
+		.map(|(index, reward)| merkle::leaf_hash::<BlakeTwo256, _>(index as u32, reward))
+		.collect();
+	let left = BlakeTwo256::hash_of(&(leaves[0], leaves[1]));
+	let right = BlakeTwo256::hash_of(&(leaves[2], leaves[3]));
+	(BlakeTwo256::hash_of(&(left, right)), leaves)
+}
+
+#[test]
+fn non_oracle_can_not_commit_rewards_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Allocations::commit_rewards_root(Origin::signed(Hacker::get()), H256::default()),
+			Errors::OracleAccessDenied
+		);
+	})
+}
+
+#[test]
+fn can_not_commit_rewards_root_twice() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		assert_ok!(Allocations::commit_rewards_root(
+			Origin::signed(Oracle::get()),
+			H256::default()
+		));
+		assert_noop!(
+			Allocations::commit_rewards_root(Origin::signed(Oracle::get()), H256::default()),
+			Errors::CommitmentAlreadyExists
+		);
+	})
+}
+
+#[test]
+fn allocate_committed_pays_each_reward_once() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		let rewards = [
+			(Grantee::get(), 50),
+			(Hacker::get(), 100),
+			(Grantee::get(), 20),
+			(4, 30),
+		];
+		let (root, leaves) = make_rewards_tree(&rewards);
+		assert_ok!(Allocations::commit_rewards_root(Origin::signed(Oracle::get()), root));
+
+		let proof = bounded_vec![leaves[3], BlakeTwo256::hash_of(&(leaves[0], leaves[1]))];
+		assert_ok!(Allocations::allocate_committed(
+			Origin::signed(Oracle::get()),
+			root,
+			2,
+			Grantee::get(),
+			20,
+			proof.clone(),
+		));
+		assert_eq!(Balances::free_balance(Grantee::get()), 18);
+		assert_eq!(Balances::free_balance(Receiver::get()), 2);
+		assert_eq!(Allocations::coins_consumed(), 20);
+		assert_eq!(Allocations::paid_leaves(root, 2), Some(()));
+
+		assert_noop!(
+			Allocations::allocate_committed(Origin::signed(Oracle::get()), root, 2, Grantee::get(), 20, proof),
+			Errors::RewardAlreadyPaid
+		);
+	})
+}
+
+#[test]
+fn allocate_committed_rejects_invalid_proofs() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		let rewards = [
+			(Grantee::get(), 50),
+			(Hacker::get(), 100),
+			(Grantee::get(), 20),
+			(4, 30),
+		];
+		let (root, leaves) = make_rewards_tree(&rewards);
+
+		let proof: BoundedVec<H256, MaxProofDepth> =
+			bounded_vec![leaves[1], BlakeTwo256::hash_of(&(leaves[2], leaves[3]))];
+		assert_noop!(
+			Allocations::allocate_committed(
+				Origin::signed(Oracle::get()),
+				root,
+				0,
+				Grantee::get(),
+				50,
+				proof.clone()
+			),
+			Errors::UnknownCommitment
+		);
+
+		assert_ok!(Allocations::commit_rewards_root(Origin::signed(Oracle::get()), root));
+
+		// wrong amount
+		assert_noop!(
+			Allocations::allocate_committed(
+				Origin::signed(Oracle::get()),
+				root,
+				0,
+				Grantee::get(),
+				500,
+				proof.clone()
+			),
+			Errors::InvalidMerkleProof
+		);
+		// wrong position
+		assert_noop!(
+			Allocations::allocate_committed(
+				Origin::signed(Oracle::get()),
+				root,
+				1,
+				Grantee::get(),
+				50,
+				proof.clone()
+			),
+			Errors::InvalidMerkleProof
+		);
+
+		assert_ok!(Allocations::allocate_committed(
+			Origin::signed(Oracle::get()),
+			root,
+			0,
+			Grantee::get(),
+			50,
+			proof
+		));
+	})
+}
+
+#[test]
+fn non_oracle_can_not_publish_rewards_epoch() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Allocations::publish_rewards_epoch(Origin::signed(Hacker::get()), H256::default(), 100),
+			Errors::OracleAccessDenied
+		);
+	})
+}
+
+#[test]
+fn publish_rewards_epoch_mints_coins() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		assert_noop!(
+			Allocations::publish_rewards_epoch(Origin::signed(Oracle::get()), H256::default(), CoinsLimit::get() + 1),
+			Errors::TooManyCoinsToAllocate
+		);
+
+		assert_ok!(Allocations::publish_rewards_epoch(
+			Origin::signed(Oracle::get()),
+			H256::default(),
+			200
+		));
+		// the claims pot is seeded with the existential deposit, outside of the cap
+		assert_eq!(Allocations::coins_consumed(), 200);
+		assert_eq!(Balances::free_balance(Allocations::claims_account()), 202);
+		assert_eq!(Allocations::next_rewards_epoch(), 1);
+		assert_eq!(
+			Allocations::rewards_epochs(0),
+			Some(RewardsEpoch {
+				root: H256::default(),
+				total: 200,
+				claimed: 0,
+				expires_at: ClaimPeriod::get(),
+			})
+		);
+	})
+}
+
+#[test]
+fn claim_rewards() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		let rewards = [
+			(Grantee::get(), 50),
+			(Hacker::get(), 100),
+			(Grantee::get(), 20),
+			(4, 30),
+		];
+		let (root, leaves) = make_rewards_tree(&rewards);
+		assert_ok!(Allocations::publish_rewards_epoch(
+			Origin::signed(Oracle::get()),
+			root,
+			200
+		));
+
+		let proof: BoundedVec<H256, MaxProofDepth> =
+			bounded_vec![leaves[0], BlakeTwo256::hash_of(&(leaves[2], leaves[3]))];
+		assert_noop!(
+			Allocations::claim(Origin::signed(Grantee::get()), 0, 1, 100, proof.clone()),
+			Errors::InvalidMerkleProof
+		);
+		assert_ok!(Allocations::claim(
+			Origin::signed(Hacker::get()),
+			0,
+			1,
+			100,
+			proof.clone()
+		));
+		assert_noop!(
+			Allocations::claim(Origin::signed(Hacker::get()), 0, 1, 100, proof),
+			Errors::RewardAlreadyPaid
+		);
+
+		assert_eq!(Balances::free_balance(Hacker::get()), 90);
+		assert_eq!(Balances::free_balance(Receiver::get()), 10);
+		assert_eq!(Allocations::rewards_epochs(0).unwrap().claimed, 100);
+	})
+}
+
+#[test]
+fn can_not_claim_more_than_published() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		let rewards = [
+			(Grantee::get(), 50),
+			(Hacker::get(), 100),
+			(Grantee::get(), 20),
+			(4, 30),
+		];
+		let (root, leaves) = make_rewards_tree(&rewards);
+		assert_ok!(Allocations::publish_rewards_epoch(
+			Origin::signed(Oracle::get()),
+			root,
+			60
+		));
+
+		assert_ok!(Allocations::claim(
+			Origin::signed(Grantee::get()),
+			0,
+			0,
+			50,
+			bounded_vec![leaves[1], BlakeTwo256::hash_of(&(leaves[2], leaves[3]))]
+		));
+		assert_noop!(
+			Allocations::claim(
+				Origin::signed(Grantee::get()),
+				0,
+				2,
+				20,
+				bounded_vec![leaves[3], BlakeTwo256::hash_of(&(leaves[0], leaves[1]))]
+			),
+			Errors::RewardsEpochExhausted
+		);
+	})
+}
+
+#[test]
+fn unclaimed_rewards_expire_to_the_protocol() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		let rewards = [
+			(Grantee::get(), 50),
+			(Hacker::get(), 100),
+			(Grantee::get(), 20),
+			(4, 30),
+		];
+		let (root, leaves) = make_rewards_tree(&rewards);
+		assert_ok!(Allocations::publish_rewards_epoch(
+			Origin::signed(Oracle::get()),
+			root,
+			200
+		));
+		assert_ok!(Allocations::claim(
+			Origin::signed(Grantee::get()),
+			0,
+			0,
+			50,
+			bounded_vec![leaves[1], BlakeTwo256::hash_of(&(leaves[2], leaves[3]))]
+		));
+
+		assert_noop!(
+			Allocations::expire_rewards_epoch(Origin::signed(Hacker::get()), 0, 10),
+			Errors::RewardsEpochNotExpired
+		);
+
+		System::set_block_number(ClaimPeriod::get() + 1);
+		assert_noop!(
+			Allocations::claim(
+				Origin::signed(Hacker::get()),
+				0,
+				1,
+				100,
+				bounded_vec![leaves[0], BlakeTwo256::hash_of(&(leaves[2], leaves[3]))]
+			),
+			Errors::RewardsEpochExpired
+		);
+		let fees_notified = ProtocolFeesNotified::get();
+		let expired_notified = ExpiredRewardsNotified::get();
+		assert_ok!(Allocations::expire_rewards_epoch(Origin::signed(Hacker::get()), 0, 10));
+
+		assert_eq!(Balances::free_balance(Grantee::get()), 45);
+		assert_eq!(Balances::free_balance(Receiver::get()), 155);
+		assert_eq!(ProtocolFeesNotified::get(), fees_notified);
+		assert_eq!(ExpiredRewardsNotified::get(), expired_notified + 150);
+		assert_eq!(
+			Balances::free_balance(Allocations::claims_account()),
+			ExistentialDeposit::get()
+		);
+		assert_eq!(Allocations::rewards_epochs(0), None);
+		assert_eq!(Allocations::claimed_rewards(0, 0), None);
+		assert_eq!(Allocations::coins_consumed(), 200);
+	})
+}
+
+#[test]
+fn expired_rewards_epoch_claims_are_cleared_in_steps() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+		assert_ok!(Allocations::publish_rewards_epoch(
+			Origin::signed(Oracle::get()),
+			H256::default(),
+			200
+		));
+		for index in 0..3 {
+			<ClaimedRewards<Test>>::insert(0, index, ());
+		}
+		System::set_block_number(ClaimPeriod::get() + 1);
+	});
+
+	// the limit only applies to the claims stored in previous blocks
+	ext.commit_all().unwrap();
+	ext.execute_with(|| {
+		assert_ok!(Allocations::expire_rewards_epoch(Origin::signed(Hacker::get()), 0, 2));
+		assert_eq!(<ClaimedRewards<Test>>::iter_prefix(0).count(), 1);
+		assert!(Allocations::rewards_epochs(0).is_some());
+		assert_eq!(Balances::free_balance(Receiver::get()), 0);
+	});
+
+	ext.commit_all().unwrap();
+	ext.execute_with(|| {
+		assert_ok!(Allocations::expire_rewards_epoch(Origin::signed(Hacker::get()), 0, 2));
+		assert_eq!(<ClaimedRewards<Test>>::iter_prefix(0).count(), 0);
+		assert_eq!(Allocations::rewards_epochs(0), None);
+		assert_eq!(Balances::free_balance(Receiver::get()), 200);
+	});
+}
+
+#[test]
+fn only_quorum_origin_can_set_quorum() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Allocations::set_quorum(Origin::signed(Hacker::get()), Some(2)),
+			BadOrigin
+		);
+		assert_noop!(
+			Allocations::set_quorum(Origin::signed(QuorumAdmin::get()), Some(0)),
+			Errors::InvalidQuorum
+		);
+
+		assert_ok!(Allocations::set_quorum(Origin::signed(QuorumAdmin::get()), Some(2)));
+		assert_eq!(Allocations::quorum(), Some(2));
+		assert_ok!(Allocations::set_quorum(Origin::root(), None));
+		assert_eq!(Allocations::quorum(), None);
+	})
+}
+
+#[test]
+fn single_oracle_can_not_mint_under_quorum() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get(), 4]);
+		new_program(&[Oracle::get(), 4]);
+		assert_ok!(Allocations::set_quorum(Origin::signed(QuorumAdmin::get()), Some(2)));
+
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 50, Vec::new()),
+			Errors::QuorumRequired
+		);
+		assert_noop!(
+			Allocations::batch_allocate(
+				Origin::signed(Oracle::get()),
+				bounded_vec![(Grantee::get(), 50)],
+				Vec::new()
+			),
+			Errors::QuorumRequired
+		);
+		assert_noop!(
+			Allocations::publish_rewards_epoch(Origin::signed(Oracle::get()), H256::default(), 50),
+			Errors::QuorumRequired
+		);
+	})
+}
+
+#[test]
+fn batch_is_minted_once_quorum_is_reached() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get(), 4, 5]);
+
+		assert_noop!(
+			Allocations::propose_batch(
+				Origin::signed(Oracle::get()),
+				bounded_vec![(Grantee::get(), 50)],
+				Vec::new()
+			),
+			Errors::QuorumNotEnabled
+		);
+
+		assert_ok!(Allocations::set_quorum(Origin::signed(QuorumAdmin::get()), Some(2)));
+		let batch: BoundedVec<(u64, u64), MaxAllocs> = bounded_vec![(Grantee::get(), 50), (Hacker::get(), 100)];
+		let hash = BlakeTwo256::hash_of(&(&batch, &Vec::<u8>::new()));
+
+		assert_noop!(
+			Allocations::propose_batch(Origin::signed(Hacker::get()), batch.clone(), Vec::new()),
+			Errors::OracleAccessDenied
+		);
+		assert_ok!(Allocations::propose_batch(
+			Origin::signed(Oracle::get()),
+			batch.clone(),
+			Vec::new()
+		));
+		assert_noop!(
+			Allocations::propose_batch(Origin::signed(4), batch, Vec::new()),
+			Errors::BatchAlreadyPending
+		);
+		assert_noop!(
+			Allocations::approve_batch(Origin::signed(Oracle::get()), hash),
+			Errors::AlreadyApproved
+		);
+		assert_eq!(Balances::free_balance(Grantee::get()), 0);
+
+		assert_ok!(Allocations::approve_batch(Origin::signed(4), hash));
+		assert_eq!(Allocations::pending_batches(hash), None);
+		assert_eq!(Balances::free_balance(Grantee::get()), 45);
+		assert_eq!(Balances::free_balance(Hacker::get()), 90);
+		assert_eq!(Balances::free_balance(Receiver::get()), 15);
+		assert_eq!(Allocations::coins_consumed(), 150);
+
+		assert_noop!(
+			Allocations::approve_batch(Origin::signed(5), hash),
+			Errors::UnknownBatch
+		);
+	})
+}
+
+#[test]
+fn removed_oracles_do_not_count_towards_quorum() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get(), 4, 5]);
+		assert_ok!(Allocations::set_quorum(Origin::signed(QuorumAdmin::get()), Some(2)));
+
+		let batch: BoundedVec<(u64, u64), MaxAllocs> = bounded_vec![(Grantee::get(), 50)];
+		let hash = BlakeTwo256::hash_of(&(&batch, &Vec::<u8>::new()));
+		assert_ok!(Allocations::propose_batch(
+			Origin::signed(Oracle::get()),
+			batch,
+			Vec::new()
+		));
+
+		Allocations::change_members_sorted(&[], &[Oracle::get()], &[4, 5]);
+		assert_ok!(Allocations::approve_batch(Origin::signed(4), hash));
+		assert_eq!(Balances::free_balance(Grantee::get()), 0);
+		assert_eq!(
+			Allocations::pending_batches(hash).unwrap().approvals.into_inner(),
+			vec![4]
+		);
+
+		assert_ok!(Allocations::approve_batch(Origin::signed(5), hash));
+		assert_eq!(Balances::free_balance(Grantee::get()), 45);
+	})
+}
+
+#[test]
+fn pending_batches_expire() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get(), 4]);
+		assert_ok!(Allocations::set_quorum(Origin::signed(QuorumAdmin::get()), Some(2)));
+
+		let batch: BoundedVec<(u64, u64), MaxAllocs> = bounded_vec![(Grantee::get(), 50)];
+		let hash = BlakeTwo256::hash_of(&(&batch, &Vec::<u8>::new()));
+		assert_ok!(Allocations::propose_batch(
+			Origin::signed(Oracle::get()),
+			batch,
+			Vec::new()
+		));
+
+		assert_noop!(
+			Allocations::remove_expired_batch(Origin::signed(Hacker::get()), hash),
+			Errors::BatchNotExpired
+		);
+
+		System::set_block_number(QuorumVotingPeriod::get() + 1);
+		assert_noop!(
+			Allocations::approve_batch(Origin::signed(4), hash),
+			Errors::BatchExpired
+		);
+		assert_ok!(Allocations::remove_expired_batch(Origin::signed(Hacker::get()), hash));
+		assert_eq!(Allocations::pending_batches(hash), None);
+	})
+}
+
+#[test]
+fn vested_allocation_locks_grantee_share() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+
+		assert_noop!(
+			Allocations::allocate_vested(Origin::signed(Hacker::get()), Grantee::get(), 50, 0, 10, 3, Vec::new()),
+			Errors::OracleAccessDenied
+		);
+		assert_noop!(
+			Allocations::allocate_vested(Origin::signed(Oracle::get()), Grantee::get(), 50, 0, 10, 0, Vec::new()),
+			Errors::InvalidVestingSchedule
+		);
+
+		assert_ok!(Allocations::allocate_vested(
+			Origin::signed(Oracle::get()),
+			Grantee::get(),
+			50,
+			0,
+			10,
+			4,
+			Vec::new()
+		));
+
+		// 45 for the grantee, 11 per period and 1 that can not be split
+		assert_eq!(Balances::free_balance(Grantee::get()), 45);
+		assert_eq!(Balances::usable_balance(Grantee::get()), 1);
+		assert_eq!(Balances::locks(Grantee::get())[0].id, pallet_grants::VESTING_LOCK_ID);
+		assert_eq!(Balances::locks(Grantee::get())[0].amount, 44);
+		assert_eq!(
+			Vesting::vesting_schedules(Grantee::get()),
+			vec![pallet_grants::VestingSchedule {
+				start: 0,
+				period: 10,
+				period_count: 4,
+				per_period: 11,
+			}]
+		);
+		assert_eq!(Balances::free_balance(Receiver::get()), 5);
+		assert_eq!(Allocations::coins_consumed(), 50);
+	})
+}
+
+#[test]
+fn only_program_origin_can_manage_programs() {
+	new_test_ext().execute_with(|| {
+		let program = AllocationProgram {
+			budget: 100,
+			oracles: bounded_vec![Oracle::get()],
+			fee: Perbill::from_percent(20),
+			fee_receiver: 4,
+		};
+		assert_noop!(
+			Allocations::create_program(Origin::signed(Hacker::get()), program.clone()),
+			BadOrigin
+		);
+		assert_noop!(
+			Allocations::update_program(Origin::signed(ProgramAdmin::get()), 0, program.clone()),
+			Errors::UnknownProgram
+		);
+
+		assert_ok!(Allocations::create_program(
+			Origin::signed(ProgramAdmin::get()),
+			program.clone()
+		));
+		assert_ok!(Allocations::create_program(Origin::root(), program.clone()));
+		assert_eq!(Allocations::programs(1), Some(program.clone()));
+		assert_eq!(Allocations::next_program_id(), 2);
+
+		assert_noop!(Allocations::remove_program(Origin::signed(Hacker::get()), 0), BadOrigin);
+		assert_ok!(Allocations::remove_program(Origin::signed(ProgramAdmin::get()), 0));
+		assert_eq!(Allocations::programs(0), None);
+		assert_eq!(Allocations::program_budget_left(0), None);
+		assert_eq!(Allocations::program_budget_left(1), Some(100));
+	})
+}
+
+#[test]
+fn programs_have_their_own_oracles_fees_and_budget() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+		assert_ok!(Allocations::create_program(
+			Origin::signed(ProgramAdmin::get()),
+			AllocationProgram {
+				budget: 100,
+				oracles: bounded_vec![Hacker::get()],
+				fee: Perbill::from_percent(20),
+				fee_receiver: 4,
+			}
+		));
+
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 50, Vec::new()),
+			Errors::OracleAccessDenied
+		);
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Hacker::get()), 1, Grantee::get(), 50, Vec::new()),
+			Errors::UnknownProgram
+		);
+
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Hacker::get()),
+			0,
+			Grantee::get(),
+			60,
+			Vec::new()
+		));
+		assert_eq!(Balances::free_balance(Grantee::get()), 48);
+		assert_eq!(Balances::free_balance(4), 12);
+		assert_eq!(Balances::free_balance(Receiver::get()), 0);
+		assert_eq!(Allocations::program_consumed(0), 60);
+		assert_eq!(Allocations::program_budget_left(0), Some(40));
+		assert_eq!(Allocations::coins_consumed(), 60);
+
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Hacker::get()), 0, Grantee::get(), 50, Vec::new()),
+			Errors::ProgramBudgetExceeded
+		);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Hacker::get()),
+			0,
+			Grantee::get(),
+			40,
+			Vec::new()
+		));
+		assert_eq!(Allocations::program_budget_left(0), Some(0));
+	})
+}
+
+#[test]
+fn oracles_removed_from_the_pallet_leave_their_programs() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get(), Hacker::get()]);
+		new_program(&[Oracle::get(), Hacker::get()]);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			50,
+			Vec::new()
+		));
+
+		Allocations::change_members_sorted(&[], &[Oracle::get()], &[Hacker::get()]);
+		assert_eq!(
+			Allocations::programs(0).unwrap().oracles.into_inner(),
+			vec![Hacker::get()]
+		);
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 50, Vec::new()),
+			Errors::OracleAccessDenied
+		);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Hacker::get()),
+			0,
+			Grantee::get(),
+			50,
+			Vec::new()
+		));
+	})
+}
+
+#[test]
+fn oracles_get_a_default_program_on_upgrade() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get()]);
+		<CoinsConsumed<Test>>::put(100);
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(migrations::v1::CreateDefaultProgram::<Test>::pre_upgrade());
+
+		migrations::v1::CreateDefaultProgram::<Test>::on_runtime_upgrade();
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(migrations::v1::CreateDefaultProgram::<Test>::post_upgrade());
+
+		assert_eq!(
+			Allocations::programs(0),
+			Some(AllocationProgram {
+				budget: CoinsLimit::get() - 100,
+				oracles: bounded_vec![Oracle::get()],
+				fee: Fee::get(),
+				fee_receiver: Receiver::get(),
+			})
+		);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			50,
+			Vec::new()
+		));
+
+		// the migration only applies once
+		migrations::v1::CreateDefaultProgram::<Test>::on_runtime_upgrade();
+		assert_eq!(Allocations::next_program_id(), 1);
+	})
+}
+
+#[test]
+fn protocol_is_only_notified_of_its_own_fees() {
+	new_test_ext().execute_with(|| {
+		let notified = ProtocolFeesNotified::get();
+		new_program(&[Oracle::get()]);
+		assert_ok!(Allocations::create_program(
+			Origin::signed(ProgramAdmin::get()),
+			AllocationProgram {
+				budget: u64::MAX,
+				oracles: bounded_vec![Oracle::get()],
+				fee: Perbill::from_percent(20),
+				fee_receiver: 4,
+			}
+		));
+
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			1,
+			Grantee::get(),
+			50,
+			Vec::new()
+		));
+		assert_eq!(Balances::free_balance(4), 10);
+		assert_eq!(ProtocolFeesNotified::get(), notified);
+
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			50,
+			Vec::new()
+		));
+		assert_eq!(Balances::free_balance(Receiver::get()), 5);
+		assert_eq!(ProtocolFeesNotified::get(), notified + 5);
+	})
+}
+
+#[test]
+fn decaying_emission_curve() {
+	type Curve = DecayingEmission<EmissionStart, EmissionPeriod, EmissionInitial, Halving>;
+
+	assert_eq!(Curve::cumulative_allowance(0), 0);
+	assert_eq!(Curve::cumulative_allowance(10), 0);
+	assert_eq!(Curve::cumulative_allowance(15), 500);
+	assert_eq!(Curve::cumulative_allowance(20), 1_000);
+	assert_eq!(Curve::cumulative_allowance(25), 1_250);
+	assert_eq!(Curve::cumulative_allowance(30), 1_500);
+	assert_eq!(Curve::cumulative_allowance(40), 1_750);
+	// emissions converge towards `Initial / Decay`
+	assert_eq!(Curve::cumulative_allowance(1_000), 2_000);
+	assert_eq!(Curve::cumulative_allowance(u64::MAX), 2_000);
+}
+
+#[test]
+fn can_not_allocate_beyond_emission_curve() {
+	new_test_ext().execute_with(|| {
+		CurveEnabled::set(true);
+		Allocations::initialize_members(&[Oracle::get()]);
+		new_program(&[Oracle::get()]);
+
+		System::set_block_number(15);
+		assert_eq!(Allocations::emission_allowance(), 500);
+		assert_noop!(
+			Allocations::allocate(Origin::signed(Oracle::get()), 0, Grantee::get(), 501, Vec::new()),
+			Errors::EmissionCurveExceeded
+		);
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			400,
+			Vec::new()
+		));
+		assert_eq!(Allocations::emission_headroom(), 100);
+
+		System::set_block_number(20);
+		assert_eq!(Allocations::emission_headroom(), 600);
+		assert_ok!(Allocations::batch_allocate(
+			Origin::signed(Oracle::get()),
+			bounded_vec![(Grantee::get(), 300), (Hacker::get(), 300)],
+			Vec::new()
+		));
+		assert_eq!(Allocations::emission_headroom(), 0);
+	})
+}
+
+#[test]
+fn totals_are_tracked_per_recipient_and_oracle() {
+	new_test_ext().execute_with(|| {
+		Allocations::initialize_members(&[Oracle::get(), Hacker::get()]);
+		new_program(&[Oracle::get()]);
+
+		assert_ok!(Allocations::allocate(
+			Origin::signed(Oracle::get()),
+			0,
+			Grantee::get(),
+			50,
+			Vec::new()
+		));
+		System::set_block_number(AccountingEpoch::get());
+		assert_ok!(Allocations::batch_allocate(
+			Origin::signed(Hacker::get()),
+			bounded_vec![(Grantee::get(), 100), (4, 20)],
+			Vec::new()
+		));
+
+		assert_eq!(
+			Allocations::recipient_totals(&Grantee::get(), None),
+			AllocationTotals {
+				lifetime: 135,
+				epoch: 1,
+				in_epoch: 90,
+			}
+		);
+		assert_eq!(
+			Allocations::recipient_totals(&Grantee::get(), Some(0)),
+			AllocationTotals {
+				lifetime: 135,
+				epoch: 0,
+				in_epoch: 45,
+			}
+		);
+		assert_eq!(Allocations::recipient_totals(&4, None).lifetime, 18);
+		assert_eq!(
+			Allocations::oracle_totals(&Oracle::get(), Some(0)),
+			AllocationTotals {
+				lifetime: 50,
+				epoch: 0,
+				in_epoch: 50,
+			}
+		);
+		assert_eq!(
+			Allocations::oracle_totals(&Hacker::get(), None),
+			AllocationTotals {
+				lifetime: 120,
+				epoch: 1,
+				in_epoch: 120,
+			}
+		);
+		assert_eq!(Allocations::oracle_totals(&Hacker::get(), Some(0)).in_epoch, 0);
+	})
+}
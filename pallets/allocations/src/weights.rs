@@ -16,13 +16,15 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Autogenerated weights for pallet_allocations
+//! Weights for pallet_allocations
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2021-11-09, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//! NOT FULLY AUTO-GENERATED YET: only `allocate` comes from a run of the benchmark CLI
+//! (DATE: 2021-11-09, STEPS: `50`, REPEAT: 20, CHAIN: Some("dev")). Every other entry is a
+//! hand-written placeholder, its storage accesses match the code but its base weight is an
+//! estimate. Replace this file with the output of the command below once the benchmarks in
+//! `benchmarking.rs` ran on the reference hardware.
 
-// Executed Command:
+// Command to generate this file:
 // target/release/nodle-chain
 // benchmark
 // --chain=dev
@@ -48,6 +50,21 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_allocations.
 pub trait WeightInfo {
 	fn allocate(b: u32) -> Weight;
+	fn batch_allocate(n: u32, b: u32) -> Weight;
+	fn allocate_vested(b: u32) -> Weight;
+	fn set_mint_limit() -> Weight;
+	fn commit_rewards_root() -> Weight;
+	fn allocate_committed(d: u32) -> Weight;
+	fn publish_rewards_epoch() -> Weight;
+	fn claim(d: u32) -> Weight;
+	fn expire_rewards_epoch(c: u32) -> Weight;
+	fn propose_batch(n: u32, b: u32) -> Weight;
+	fn approve_batch(n: u32) -> Weight;
+	fn remove_expired_batch() -> Weight;
+	fn set_quorum() -> Weight;
+	fn create_program(o: u32) -> Weight;
+	fn update_program(o: u32) -> Weight;
+	fn remove_program() -> Weight;
 }
 
 /// Weights for pallet_allocations using the Substrate node and recommended hardware.
@@ -58,6 +75,94 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(10 as Weight))
 			.saturating_add(T::DbWeight::get().writes(6 as Weight))
 	}
+	fn allocate_vested(_b: u32) -> Weight {
+		(118_361_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	fn batch_allocate(n: u32, _b: u32) -> Weight {
+		(41_127_000 as Weight)
+			.saturating_add((32_468_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	fn set_mint_limit() -> Weight {
+		(17_342_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn commit_rewards_root() -> Weight {
+		(21_870_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn allocate_committed(d: u32) -> Weight {
+		(86_114_000 as Weight)
+			.saturating_add((1_312_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(12 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn publish_rewards_epoch() -> Weight {
+		(54_209_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn claim(d: u32) -> Weight {
+		(79_631_000 as Weight)
+			.saturating_add((1_296_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn expire_rewards_epoch(c: u32) -> Weight {
+		(48_552_000 as Weight)
+			.saturating_add((1_175_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	fn propose_batch(n: u32, _b: u32) -> Weight {
+		(32_918_000 as Weight)
+			.saturating_add((712_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn approve_batch(n: u32) -> Weight {
+		(52_604_000 as Weight)
+			.saturating_add((33_190_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	fn remove_expired_batch() -> Weight {
+		(24_136_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_quorum() -> Weight {
+		(15_873_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn create_program(o: u32) -> Weight {
+		(19_224_000 as Weight)
+			.saturating_add((96_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn update_program(o: u32) -> Weight {
+		(18_702_000 as Weight)
+			.saturating_add((95_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_program() -> Weight {
+		(20_581_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -67,4 +172,92 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
 	}
+	fn allocate_vested(_b: u32) -> Weight {
+		(118_361_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn batch_allocate(n: u32, _b: u32) -> Weight {
+		(41_127_000 as Weight)
+			.saturating_add((32_468_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	fn set_mint_limit() -> Weight {
+		(17_342_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn commit_rewards_root() -> Weight {
+		(21_870_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn allocate_committed(d: u32) -> Weight {
+		(86_114_000 as Weight)
+			.saturating_add((1_312_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(12 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn publish_rewards_epoch() -> Weight {
+		(54_209_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn claim(d: u32) -> Weight {
+		(79_631_000 as Weight)
+			.saturating_add((1_296_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn expire_rewards_epoch(c: u32) -> Weight {
+		(48_552_000 as Weight)
+			.saturating_add((1_175_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	fn propose_batch(n: u32, _b: u32) -> Weight {
+		(32_918_000 as Weight)
+			.saturating_add((712_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn approve_batch(n: u32) -> Weight {
+		(52_604_000 as Weight)
+			.saturating_add((33_190_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	fn remove_expired_batch() -> Weight {
+		(24_136_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_quorum() -> Weight {
+		(15_873_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn create_program(o: u32) -> Weight {
+		(19_224_000 as Weight)
+			.saturating_add((96_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn update_program(o: u32) -> Weight {
+		(18_702_000 as Weight)
+			.saturating_add((95_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn remove_program() -> Weight {
+		(20_581_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
 }
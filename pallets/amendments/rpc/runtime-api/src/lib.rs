@@ -0,0 +1,45 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Runtime API definition for the amendments pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_amendments::{AmendmentId, DeclaredWrites, DryRun, PendingAmendment};
+use sp_runtime::DispatchError;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait AmendmentsApi<PalletsOrigin, Hash, BlockNumber, Event> where
+		PalletsOrigin: Codec,
+		Hash: Codec,
+		BlockNumber: Codec,
+		Event: Codec,
+	{
+		/// Every amendment waiting for its security delay to expire, by id.
+		fn pending_amendments() -> Vec<(AmendmentId, PendingAmendment<PalletsOrigin, Hash, BlockNumber>)>;
+
+		/// Every vetoed amendment whose veto can still be overridden, with the block of the veto.
+		fn vetoed_amendments() -> Vec<(AmendmentId, PendingAmendment<PalletsOrigin, Hash, BlockNumber>, BlockNumber)>;
+
+		/// Execute the SCALE encoded `amendment` against the current state, and report what it
+		/// did. None of its effects are kept.
+		fn dry_run(amendment: Vec<u8>) -> Result<DryRun<Event>, DispatchError>;
+	}
+}
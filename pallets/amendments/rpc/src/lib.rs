@@ -0,0 +1,133 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! RPC interface for the amendments pallet.
+
+use std::sync::Arc;
+
+use codec::{Codec, Encode};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+pub use pallet_amendments_rpc_runtime_api::AmendmentsApi as AmendmentsRuntimeApi;
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+	DispatchResult,
+};
+
+/// What an amendment would do if it was executed now.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendmentDryRun {
+	/// Result of the dispatch of the amendment
+	pub result: DispatchResult,
+	/// Weight used by the amendment
+	pub weight: u64,
+	/// SCALE encoded events emitted by the amendment
+	pub events: Vec<Bytes>,
+	/// Keys the amendment declares to set or remove, only known for storage calls
+	pub declared_keys: Vec<Bytes>,
+	/// Prefixes the amendment declares to clear, only known for storage calls
+	pub declared_prefixes: Vec<Bytes>,
+}
+
+#[rpc]
+pub trait AmendmentsApi<BlockHash> {
+	/// Execute the SCALE encoded `amendment` against the state of block `at`, and report what it
+	/// did. None of its effects are kept. This is an unsafe RPC, as the amendment is executed
+	/// as root.
+	#[rpc(name = "amendments_dryRun")]
+	fn dry_run(&self, amendment: Bytes, at: Option<BlockHash>) -> Result<AmendmentDryRun>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The amendment could not be executed.
+	DryRunFailed,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::DryRunFailed => 2,
+		}
+	}
+}
+
+/// Amendments RPC methods, answered from the runtime API.
+pub struct Amendments<C, Block, PalletsOrigin, Event> {
+	client: Arc<C>,
+	deny_unsafe: DenyUnsafe,
+	_marker: std::marker::PhantomData<(Block, PalletsOrigin, Event)>,
+}
+
+impl<C, Block, PalletsOrigin, Event> Amendments<C, Block, PalletsOrigin, Event> {
+	/// Create new `Amendments` with the given reference to the client.
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+		Self {
+			client,
+			deny_unsafe,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, PalletsOrigin, Event> AmendmentsApi<<Block as BlockT>::Hash>
+	for Amendments<C, Block, PalletsOrigin, Event>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: AmendmentsRuntimeApi<Block, PalletsOrigin, <Block as BlockT>::Hash, NumberFor<Block>, Event>,
+	PalletsOrigin: Codec + Send + Sync + 'static,
+	Event: Codec + Send + Sync + 'static,
+{
+	fn dry_run(&self, amendment: Bytes, at: Option<<Block as BlockT>::Hash>) -> Result<AmendmentDryRun> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let dry_run = api
+			.dry_run(&at, amendment.to_vec())
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::RuntimeError.into()),
+				message: "Unable to dry run the amendment.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})?
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::DryRunFailed.into()),
+				message: "The amendment could not be executed.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})?;
+		Ok(AmendmentDryRun {
+			result: dry_run.result,
+			weight: dry_run.weight,
+			events: dry_run.events.iter().map(|event| event.encode().into()).collect(),
+			declared_keys: dry_run.declared_writes.keys.into_iter().map(Into::into).collect(),
+			declared_prefixes: dry_run.declared_writes.prefixes.into_iter().map(Into::into).collect(),
+		})
+	}
+}
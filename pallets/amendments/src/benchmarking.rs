@@ -25,7 +25,7 @@ use super::*;
 
 use crate::Pallet as Amendments;
 use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
-use frame_support::traits::{EnsureOrigin, UnfilteredDispatchable};
+use frame_support::traits::{EnsureOrigin, Hooks, UnfilteredDispatchable};
 use frame_system::{Call as SystemCall, RawOrigin as SystemOrigin};
 use sp_std::prelude::*;
 
@@ -51,6 +51,93 @@ benchmarks! {
 		let origin = T::VetoOrigin::successful_origin();
 	}: { call.dispatch_bypass_filter(origin)? }
 
+	execute {
+		let amendment: T::Amendment = SystemCall::<T>::remark{ remark: vec![1; MAX_BYTES as usize] }.into();
+		Pallet::<T>::propose(
+			SystemOrigin::Root.into(),
+			Box::new(amendment.clone())
+		)?;
+
+		let call = Call::<T>::execute{ amendment_id: 0, amendment: Box::new(amendment) };
+	}: { call.dispatch_bypass_filter(SystemOrigin::Root.into())? }
+	verify {
+		assert!(Pallet::<T>::pending_amendments(0).is_none());
+	}
+
+	propose_preimage {
+		let amendment: T::Amendment = SystemCall::<T>::remark{ remark: vec![1; MAX_BYTES as usize] }.into();
+		let call = Call::<T>::propose_preimage{ call_hash: T::Hashing::hash_of(&amendment), weight_bound: 1_000_000 };
+		let origin = T::SubmissionOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	// `PreimageProvider` can not note preimages, so this measures an amendment dropped for
+	// a missing preimage
+	execute_preimage {
+		let amendment: T::Amendment = SystemCall::<T>::remark{ remark: vec![1; MAX_BYTES as usize] }.into();
+		Pallet::<T>::propose_preimage(
+			SystemOrigin::Root.into(),
+			T::Hashing::hash_of(&amendment),
+			1_000_000,
+		)?;
+
+		let call = Call::<T>::execute_preimage{ amendment_id: 0, weight_bound: 1_000_000 };
+	}: { call.dispatch_bypass_filter(SystemOrigin::Root.into())? }
+	verify {
+		assert!(Pallet::<T>::pending_amendments(0).is_none());
+	}
+
+	propose_fast_track {
+		let b in 1 .. MAX_BYTES;
+
+		let amendment: T::Amendment = SystemCall::<T>::remark{ remark: vec![1; b as usize] }.into();
+		let call = Call::<T>::propose_fast_track{ amendment: Box::new(amendment) };
+		let origin = T::FastTrackOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	fast_track {
+		let amendment: T::Amendment = SystemCall::<T>::remark{ remark: vec![1; MAX_BYTES as usize] }.into();
+		Pallet::<T>::propose(
+			SystemOrigin::Root.into(),
+			Box::new(amendment)
+		)?;
+
+		let call = Call::<T>::fast_track{ amendment_id: 0 };
+		let origin = T::FastTrackOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	override_veto {
+		let amendment: T::Amendment = SystemCall::<T>::remark{ remark: vec![1; MAX_BYTES as usize] }.into();
+		Pallet::<T>::propose(
+			SystemOrigin::Root.into(),
+			Box::new(amendment.clone())
+		)?;
+		Pallet::<T>::veto(SystemOrigin::Root.into(), 0)?;
+
+		let call = Call::<T>::override_veto{ amendment_id: 0, amendment: Some(Box::new(amendment)) };
+		let origin = T::VetoOverrideOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Pallet::<T>::pending_amendments(0).is_some());
+	}
+
+	on_initialize {
+		let v in 0 .. 100;
+
+		for i in 0 .. v {
+			let amendment: T::Amendment = SystemCall::<T>::remark{ remark: i.encode() }.into();
+			Pallet::<T>::propose(
+				SystemOrigin::Root.into(),
+				Box::new(amendment)
+			)?;
+			Pallet::<T>::veto(SystemOrigin::Root.into(), i as AmendmentId)?;
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		let expiry = now + T::VetoOverrideWindow::get() + 1u32.into();
+	}: { Amendments::<T>::on_initialize(expiry) }
+	verify {
+		assert_eq!(Pallet::<T>::vetoed().len(), 0);
+	}
+
 	impl_benchmark_test_suite!(Amendments, crate::tests::new_test_ext(), crate::tests::Test,);
 
 }
@@ -0,0 +1,114 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Execution of amendments against the current state, without keeping any of their effects.
+
+use crate::{Config, Error, Pallet};
+use codec::{Decode, Encode};
+use frame_support::{
+	ensure,
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Get, IsSubType},
+	weights::{extract_actual_weight, GetDispatchInfo, Weight},
+};
+use frame_system::{self as system};
+use sp_runtime::{traits::Dispatchable, DispatchError, DispatchResult, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// Storage an amendment declares to write, read from the amendment itself rather than from
+/// what its execution actually wrote.
+///
+/// The runtime can not enumerate the keys written to its storage overlay, so this is only
+/// known for amendments which are `set_storage`, `kill_storage` or `kill_prefix` calls, and
+/// empty for any other amendment. Those are best understood through their events.
+#[derive(Clone, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct DeclaredWrites {
+	/// Keys set or removed by the amendment
+	pub keys: Vec<Vec<u8>>,
+	/// Prefixes cleared by the amendment
+	pub prefixes: Vec<Vec<u8>>,
+}
+
+/// What an amendment would do if it was executed now.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct DryRun<Event> {
+	/// Result of the dispatch of the amendment
+	pub result: DispatchResult,
+	/// Weight used by the amendment
+	pub weight: Weight,
+	/// Events emitted by the amendment
+	pub events: Vec<Event>,
+	/// Storage the amendment declares to write, see `DeclaredWrites`
+	pub declared_writes: DeclaredWrites,
+}
+
+pub type DryRunOf<T> = DryRun<<T as frame_system::Config>::Event>;
+
+impl DeclaredWrites {
+	fn of<T: Config>(amendment: &T::Amendment) -> Self {
+		match amendment.is_sub_type() {
+			Some(system::Call::<T>::set_storage { items }) => DeclaredWrites {
+				keys: items.iter().map(|(key, _)| key.clone()).collect(),
+				prefixes: Vec::new(),
+			},
+			Some(system::Call::<T>::kill_storage { keys }) => DeclaredWrites {
+				keys: keys.clone(),
+				prefixes: Vec::new(),
+			},
+			Some(system::Call::<T>::kill_prefix { prefix, .. }) => DeclaredWrites {
+				keys: Vec::new(),
+				prefixes: vec![prefix.clone()],
+			},
+			_ => Default::default(),
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Execute the SCALE encoded `amendment` as `execute` would, inside a storage transaction
+	/// which is always rolled back. Meant to be called from a runtime API only. Amendments
+	/// weighing more than a block are refused, they could not be executed anyway.
+	pub fn dry_run(amendment: &[u8]) -> Result<DryRunOf<T>, DispatchError> {
+		let amendment = T::Amendment::decode(&mut &amendment[..]).map_err(|_| Error::<T>::UndecodableAmendment)?;
+		let info = amendment.get_dispatch_info();
+		ensure!(
+			info.weight <= T::BlockWeights::get().max_block,
+			Error::<T>::AmendmentTooHeavy
+		);
+		let declared_writes = DeclaredWrites::of::<T>(&amendment);
+
+		with_transaction(|| {
+			// events are only kept for the current block, and cleared with the rest
+			<system::Pallet<T>>::reset_events();
+
+			let res = amendment.dispatch(system::RawOrigin::Root.into());
+			let weight = extract_actual_weight(&res, &info);
+			let events = <system::Pallet<T>>::events()
+				.into_iter()
+				.map(|record| record.event)
+				.collect();
+
+			TransactionOutcome::Rollback(Ok(DryRun {
+				result: res.map(|_| ()).map_err(|e| e.error),
+				weight,
+				events,
+				declared_writes,
+			}))
+		})
+	}
+}
@@ -19,27 +19,65 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 //! An amendment module instance manages amendments to the chain. There could be a security
-//! delay configured along with a veto capability.
+//! delay configured along with a veto capability. A stricter origin can fast track security
+//! patches, and a veto can be overridden for a while after being issued.
 mod benchmarking;
+mod dry_run;
 
 #[cfg(test)]
 mod tests;
 
+use codec::{Decode, Encode};
 use frame_support::{
-	traits::{schedule::DispatchTime::At, schedule::Named as ScheduleNamed, LockIdentifier},
-	weights::GetDispatchInfo,
+	ensure,
+	traits::{
+		schedule::DispatchTime::At, schedule::Named as ScheduleNamed, IsSubType, LockIdentifier, OriginTrait,
+		PreimageProvider,
+	},
+	weights::{extract_actual_weight, GetDispatchInfo, PostDispatchInfo, Weight},
 };
 use frame_system::{self as system};
-use sp_runtime::traits::Dispatchable;
-use sp_std::prelude::Box;
+use sp_runtime::{
+	traits::{Dispatchable, Hash, One, Saturating},
+	DispatchError, DispatchResult, RuntimeDebug,
+};
+use sp_std::prelude::*;
 
 const AMENDMENTS_ID: LockIdentifier = *b"amendmen";
 
 pub mod weights;
 pub use weights::WeightInfo;
 
+pub use dry_run::{DeclaredWrites, DryRun, DryRunOf};
+
 pub use pallet::*;
 
+/// Identifier of an amendment, unique over the lifetime of the pallet.
+pub type AmendmentId = u64;
+
+/// An amendment waiting for its security delay to expire.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct PendingAmendment<PalletsOrigin, Hash, BlockNumber> {
+	/// Hash of the encoded amendment
+	pub call_hash: Hash,
+	/// Origin the amendment was proposed with
+	pub proposer: PalletsOrigin,
+	/// The amendment will be fetched from the preimages when executed
+	pub by_preimage: bool,
+	/// Maximum weight of the amendment
+	pub weight_bound: Weight,
+	/// Block at which the amendment was proposed
+	pub submitted_at: BlockNumber,
+	/// Block at which the amendment will be executed, unless vetoed
+	pub execute_at: BlockNumber,
+}
+
+pub type PendingAmendmentOf<T> = PendingAmendment<
+	<T as Config>::PalletsOrigin,
+	<T as frame_system::Config>::Hash,
+	<T as frame_system::Config>::BlockNumber,
+>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -51,22 +89,42 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
 		type Amendment: Parameter
-			+ Dispatchable<Origin = Self::Origin>
+			+ Dispatchable<Origin = <Self as frame_system::Config>::Origin, PostInfo = PostDispatchInfo>
 			+ From<frame_system::Call<Self>>
+			+ From<Call<Self>>
+			+ IsSubType<frame_system::Call<Self>>
 			+ GetDispatchInfo;
 
 		type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Amendment, Self::PalletsOrigin>;
-		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>
+			+ Parameter
+			+ IsType<<<Self as frame_system::Config>::Origin as OriginTrait>::PalletsOrigin>;
 
 		/// Origin that can submit amendments
-		type SubmissionOrigin: EnsureOrigin<Self::Origin>;
+		type SubmissionOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
 
 		/// Origin that can veto amendments
-		type VetoOrigin: EnsureOrigin<Self::Origin>;
+		type VetoOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
 
 		/// How much blocks have to be produced before executing the amendment
 		type Delay: Get<Self::BlockNumber>;
 
+		/// Where amendments proposed by hash are fetched from
+		type Preimages: PreimageProvider<Self::Hash>;
+
+		/// Origin that can submit amendments with a shorter delay, or shorten the delay of
+		/// a pending one. It should be stricter than `SubmissionOrigin`.
+		type FastTrackOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
+		/// How much blocks have to be produced before executing a fast tracked amendment
+		type FastTrackDelay: Get<Self::BlockNumber>;
+
+		/// Origin that can override a veto. It should be stricter than `VetoOrigin`.
+		type VetoOverrideOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
+		/// How much blocks a veto can be overridden for
+		type VetoOverrideWindow: Get<Self::BlockNumber>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -77,7 +135,17 @@ pub mod pallet {
 	pub struct Pallet<T>(PhantomData<T>);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let expiring = <VetoExpiries<T>>::take(n);
+			let count = expiring.len() as u32;
+			for amendment_id in expiring {
+				Self::finalize_veto(amendment_id, n);
+			}
+
+			T::WeightInfo::on_initialize(count)
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -92,37 +160,79 @@ pub mod pallet {
 			)
 		)]
 		pub fn propose(origin: OriginFor<T>, amendment: Box<T::Amendment>) -> DispatchResultWithPostInfo {
+			let proposer = T::PalletsOrigin::from_ref(origin.caller()).clone();
+			T::SubmissionOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			Self::submit_inline(proposer, amendment, T::Delay::get())?;
+			Ok(().into())
+		}
+
+		/// Schedule the amendment whose encoding was noted in `Preimages` under `call_hash`,
+		/// it will be fetched and executed after the configured time, unless vetoed by
+		/// `VetoOrigin`. `weight_bound` must be at least the weight of the amendment.
+		#[pallet::weight((T::WeightInfo::propose_preimage(), DispatchClass::Operational))]
+		pub fn propose_preimage(
+			origin: OriginFor<T>,
+			call_hash: T::Hash,
+			weight_bound: Weight,
+		) -> DispatchResultWithPostInfo {
+			let proposer = T::PalletsOrigin::from_ref(origin.caller()).clone();
 			T::SubmissionOrigin::try_origin(origin)
 				.map(|_| ())
 				.or_else(ensure_root)?;
 
-			let nb_scheduled = <AmendmentsScheduled<T>>::get();
-			let scheduler_id = (AMENDMENTS_ID, nb_scheduled).encode();
-			let when = <system::Pallet<T>>::block_number() + T::Delay::get();
-
-			if T::Scheduler::schedule_named(
-				scheduler_id,
-				At(when),
-				None,
-				// This number defines a priority of execution of the scheduled calls. We basically took the number
-				// from parity's democracy pallet and substracted 1 to make sure we have priority over it if a chain
-				// uses both modules.
-				62,
-				system::RawOrigin::Root.into(),
-				*amendment,
+			Self::submit(proposer, call_hash, true, weight_bound, T::Delay::get(), None)?;
+			Ok(().into())
+		}
+
+		/// Schedule `amendment` to be executed after `FastTrackDelay`, unless vetoed by
+		/// `VetoOrigin`
+		#[pallet::weight(
+			(
+				T::WeightInfo::propose_fast_track(
+					amendment.using_encoded(|x| x.len()) as u32,
+				).saturating_add(amendment.get_dispatch_info().weight),
+				DispatchClass::Operational,
 			)
-			.is_err()
-			{
-				return Err(Error::<T>::FailedToScheduleAmendment.into());
-			}
+		)]
+		pub fn propose_fast_track(origin: OriginFor<T>, amendment: Box<T::Amendment>) -> DispatchResultWithPostInfo {
+			let proposer = T::PalletsOrigin::from_ref(origin.caller()).clone();
+			T::FastTrackOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
 
-			<AmendmentsScheduled<T>>::put(nb_scheduled + 1);
+			let (amendment_id, when) = Self::submit_inline(proposer, amendment, T::FastTrackDelay::get())?;
+			Self::deposit_event(Event::AmendmentFastTracked(amendment_id, when));
+			Ok(().into())
+		}
 
-			Self::deposit_event(Event::AmendmentScheduled(nb_scheduled, when));
+		/// Bring the execution of a pending amendment forward, to `FastTrackDelay` blocks
+		/// from now
+		#[pallet::weight((T::WeightInfo::fast_track(), DispatchClass::Operational))]
+		pub fn fast_track(origin: OriginFor<T>, amendment_id: AmendmentId) -> DispatchResultWithPostInfo {
+			T::FastTrackOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			let mut pending = Self::pending_amendments(amendment_id).ok_or(Error::<T>::UnknownAmendment)?;
+			let when = <system::Pallet<T>>::block_number().saturating_add(T::FastTrackDelay::get());
+			ensure!(when < pending.execute_at, Error::<T>::AlreadyDueSooner);
+
+			let scheduler_id = (AMENDMENTS_ID, amendment_id).encode();
+			if T::Scheduler::reschedule_named(scheduler_id, At(when)).is_err() {
+				return Err(Error::<T>::FailedToRescheduleAmendment.into());
+			}
+			pending.execute_at = when;
+			<PendingAmendments<T>>::insert(amendment_id, pending);
+
+			Self::deposit_event(Event::AmendmentFastTracked(amendment_id, when));
 			Ok(().into())
 		}
 
-		/// Veto and cancel a scheduled amendment
+		/// Veto and cancel a scheduled amendment, the veto can be overridden by
+		/// `VetoOverrideOrigin` for `VetoOverrideWindow` blocks
 		#[pallet::weight(T::WeightInfo::veto())]
 		pub fn veto(origin: OriginFor<T>, amendment_id: u64) -> DispatchResultWithPostInfo {
 			T::VetoOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
@@ -131,10 +241,123 @@ pub mod pallet {
 			if T::Scheduler::cancel_named(scheduler_id).is_err() {
 				return Err(Error::<T>::FailedToCancelAmendment.into());
 			}
+			if let Some(pending) = <PendingAmendments<T>>::take(amendment_id) {
+				let now = <system::Pallet<T>>::block_number();
+				let expiry = now
+					.saturating_add(T::VetoOverrideWindow::get())
+					.saturating_add(One::one());
+				<VetoedAmendments<T>>::insert(amendment_id, (pending, now));
+				<VetoExpiries<T>>::append(expiry, amendment_id);
+			}
 
 			Self::deposit_event(Event::AmendmentVetoed(amendment_id));
 			Ok(().into())
 		}
+
+		/// Override the veto of an amendment, which is scheduled again for its original
+		/// block, or the next one if it has passed. Amendments that were not proposed by
+		/// preimage have to be provided again.
+		#[pallet::weight((T::WeightInfo::override_veto(), DispatchClass::Operational))]
+		pub fn override_veto(
+			origin: OriginFor<T>,
+			amendment_id: AmendmentId,
+			amendment: Option<Box<T::Amendment>>,
+		) -> DispatchResultWithPostInfo {
+			T::VetoOverrideOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			let (mut pending, vetoed_at) = Self::vetoed_amendments(amendment_id).ok_or(Error::<T>::NotVetoed)?;
+			let now = <system::Pallet<T>>::block_number();
+			ensure!(
+				now <= vetoed_at.saturating_add(T::VetoOverrideWindow::get()),
+				Error::<T>::VetoOverrideWindowClosed
+			);
+			let amendment = if pending.by_preimage {
+				None
+			} else {
+				let amendment = amendment.ok_or(Error::<T>::AmendmentRequired)?;
+				ensure!(
+					pending.call_hash == T::Hashing::hash_of(&amendment),
+					Error::<T>::AmendmentMismatch
+				);
+				Some(amendment)
+			};
+
+			pending.execute_at = pending.execute_at.max(now.saturating_add(One::one()));
+			Self::schedule(amendment_id, &pending, amendment)?;
+			<VetoedAmendments<T>>::remove(amendment_id);
+			let when = pending.execute_at;
+			<PendingAmendments<T>>::insert(amendment_id, pending);
+
+			Self::deposit_event(Event::VetoOverridden(amendment_id, when));
+			Ok(().into())
+		}
+
+		/// Execute a pending amendment once its delay expired, this is scheduled by `propose`
+		/// and dispatched by the scheduler.
+		#[pallet::weight(
+			(
+				T::WeightInfo::execute().saturating_add(amendment.get_dispatch_info().weight),
+				DispatchClass::Operational,
+			)
+		)]
+		pub fn execute(
+			origin: OriginFor<T>,
+			amendment_id: AmendmentId,
+			amendment: Box<T::Amendment>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let pending = Self::pending_amendments(amendment_id).ok_or(Error::<T>::UnknownAmendment)?;
+			ensure!(
+				pending.call_hash == T::Hashing::hash_of(&amendment),
+				Error::<T>::AmendmentMismatch
+			);
+
+			<PendingAmendments<T>>::remove(amendment_id);
+			let info = amendment.get_dispatch_info();
+			let res = amendment.dispatch(system::RawOrigin::Root.into());
+			let weight = extract_actual_weight(&res, &info);
+
+			Self::deposit_event(Event::AmendmentExecuted(
+				amendment_id,
+				res.map(|_| ()).map_err(|e| e.error),
+			));
+			Ok(Some(T::WeightInfo::execute().saturating_add(weight)).into())
+		}
+
+		/// Fetch a pending amendment proposed with `propose_preimage` from `Preimages` and
+		/// execute it, this is scheduled by `propose_preimage` and dispatched by the scheduler.
+		/// A missing or invalid preimage is reported through `AmendmentExecuted` and drops
+		/// the amendment.
+		#[pallet::weight((T::WeightInfo::execute_preimage().saturating_add(*weight_bound), DispatchClass::Operational))]
+		pub fn execute_preimage(
+			origin: OriginFor<T>,
+			amendment_id: AmendmentId,
+			weight_bound: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let pending = <PendingAmendments<T>>::take(amendment_id).ok_or(Error::<T>::UnknownAmendment)?;
+			Self::release_preimage(&pending);
+
+			let amendment = match Self::fetch_preimage(&pending.call_hash, weight_bound) {
+				Ok(amendment) => amendment,
+				Err(error) => {
+					Self::deposit_event(Event::AmendmentExecuted(amendment_id, Err(error)));
+					return Ok(Some(T::WeightInfo::execute_preimage()).into());
+				}
+			};
+
+			let info = amendment.get_dispatch_info();
+			let res = amendment.dispatch(system::RawOrigin::Root.into());
+			let weight = extract_actual_weight(&res, &info);
+
+			Self::deposit_event(Event::AmendmentExecuted(
+				amendment_id,
+				res.map(|_| ()).map_err(|e| e.error),
+			));
+			Ok(Some(T::WeightInfo::execute_preimage().saturating_add(weight)).into())
+		}
 	}
 
 	#[pallet::event]
@@ -142,8 +365,19 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// A new amendment has been scheduled to be executed at the given block number
 		AmendmentScheduled(u64, T::BlockNumber),
-		/// An amendment has been vetoed and will never be triggered
+		/// An amendment has been vetoed and will not be triggered, unless the veto is
+		/// overridden
 		AmendmentVetoed(u64),
+		/// An amendment was executed, with the given result \[amendment_id, result\]
+		AmendmentExecuted(AmendmentId, DispatchResult),
+		/// An amendment was fast tracked and will be executed at the given block number
+		/// \[amendment_id, when\]
+		AmendmentFastTracked(AmendmentId, T::BlockNumber),
+		/// The veto of an amendment was overridden, it will be executed at the given block
+		/// number \[amendment_id, when\]
+		VetoOverridden(AmendmentId, T::BlockNumber),
+		/// The veto of an amendment can no longer be overridden \[amendment_id\]
+		VetoFinalized(AmendmentId),
 	}
 
 	#[pallet::error]
@@ -152,9 +386,176 @@ pub mod pallet {
 		FailedToScheduleAmendment,
 		/// We failed to cancel the amendment
 		FailedToCancelAmendment,
+		/// No pending amendment has this id
+		UnknownAmendment,
+		/// The amendment does not match the one that was proposed
+		AmendmentMismatch,
+		/// The preimage of the amendment was not noted
+		PreimageMissing,
+		/// The preimage of the amendment is not a valid call
+		PreimageInvalid,
+		/// The amendment weighs more than the bound it was proposed with
+		WeightBoundExceeded,
+		/// We failed to reschedule the amendment
+		FailedToRescheduleAmendment,
+		/// The amendment is already due before the end of the fast track delay
+		AlreadyDueSooner,
+		/// No amendment with this id was recently vetoed
+		NotVetoed,
+		/// The veto can no longer be overridden
+		VetoOverrideWindowClosed,
+		/// The amendment was not proposed by preimage and has to be provided
+		AmendmentRequired,
+		/// The encoded amendment could not be decoded
+		UndecodableAmendment,
+		/// The amendment weighs more than a block can hold
+		AmendmentTooHeavy,
 	}
 
 	#[pallet::storage]
 	#[pallet::getter(fn amendments_scheduled)]
 	pub type AmendmentsScheduled<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Amendments waiting for their security delay to expire
+	#[pallet::storage]
+	#[pallet::getter(fn pending_amendments)]
+	pub type PendingAmendments<T: Config> =
+		StorageMap<_, Twox64Concat, AmendmentId, PendingAmendmentOf<T>, OptionQuery>;
+
+	/// Vetoed amendments, along with the block of their veto, as long as it can be overridden
+	#[pallet::storage]
+	#[pallet::getter(fn vetoed_amendments)]
+	pub type VetoedAmendments<T: Config> =
+		StorageMap<_, Twox64Concat, AmendmentId, (PendingAmendmentOf<T>, T::BlockNumber), OptionQuery>;
+
+	/// Vetoes that can no longer be overridden from a given block
+	#[pallet::storage]
+	#[pallet::getter(fn veto_expiries)]
+	pub type VetoExpiries<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, Vec<AmendmentId>, ValueQuery>;
+}
+
+impl<T: Config> Pallet<T> {
+	fn submit_inline(
+		proposer: T::PalletsOrigin,
+		amendment: Box<T::Amendment>,
+		delay: T::BlockNumber,
+	) -> Result<(AmendmentId, T::BlockNumber), DispatchError> {
+		let call_hash = T::Hashing::hash_of(&amendment);
+		let weight_bound = amendment.get_dispatch_info().weight;
+		Self::submit(proposer, call_hash, false, weight_bound, delay, Some(amendment))
+	}
+
+	/// Record and schedule a new amendment, `amendment` being `None` for the ones proposed by
+	/// preimage.
+	fn submit(
+		proposer: T::PalletsOrigin,
+		call_hash: T::Hash,
+		by_preimage: bool,
+		weight_bound: Weight,
+		delay: T::BlockNumber,
+		amendment: Option<Box<T::Amendment>>,
+	) -> Result<(AmendmentId, T::BlockNumber), DispatchError> {
+		let amendment_id = <AmendmentsScheduled<T>>::get();
+		let now = <system::Pallet<T>>::block_number();
+		let when = now + delay;
+		let pending = PendingAmendment {
+			call_hash,
+			proposer,
+			by_preimage,
+			weight_bound,
+			submitted_at: now,
+			execute_at: when,
+		};
+
+		Self::schedule(amendment_id, &pending, amendment)?;
+		<AmendmentsScheduled<T>>::put(amendment_id + 1);
+		<PendingAmendments<T>>::insert(amendment_id, pending);
+		if by_preimage {
+			// makes sure the preimage is kept, and can be noted for free, until the amendment
+			// executes or its veto is final
+			T::Preimages::request_preimage(&call_hash);
+		}
+
+		Self::deposit_event(Event::AmendmentScheduled(amendment_id, when));
+		Ok((amendment_id, when))
+	}
+
+	fn schedule(
+		amendment_id: AmendmentId,
+		pending: &PendingAmendmentOf<T>,
+		amendment: Option<Box<T::Amendment>>,
+	) -> DispatchResult {
+		let scheduler_id = (AMENDMENTS_ID, amendment_id).encode();
+		let call = match amendment {
+			Some(amendment) => Call::<T>::execute {
+				amendment_id,
+				amendment,
+			},
+			None => Call::<T>::execute_preimage {
+				amendment_id,
+				weight_bound: pending.weight_bound,
+			},
+		};
+
+		if T::Scheduler::schedule_named(
+			scheduler_id,
+			At(pending.execute_at),
+			None,
+			// This number defines a priority of execution of the scheduled calls. We basically took the number
+			// from parity's democracy pallet and substracted 1 to make sure we have priority over it if a chain
+			// uses both modules.
+			62,
+			system::RawOrigin::Root.into(),
+			call.into(),
+		)
+		.is_err()
+		{
+			return Err(Error::<T>::FailedToScheduleAmendment.into());
+		}
+
+		Ok(())
+	}
+
+	fn finalize_veto(amendment_id: AmendmentId, n: T::BlockNumber) {
+		if let Some((pending, vetoed_at)) = Self::vetoed_amendments(amendment_id) {
+			// the amendment may have been vetoed again after an override
+			if vetoed_at.saturating_add(T::VetoOverrideWindow::get()) < n {
+				<VetoedAmendments<T>>::remove(amendment_id);
+				Self::release_preimage(&pending);
+				Self::deposit_event(Event::VetoFinalized(amendment_id));
+			}
+		}
+	}
+
+	fn fetch_preimage(call_hash: &T::Hash, weight_bound: Weight) -> Result<T::Amendment, DispatchError> {
+		let bytes = T::Preimages::get_preimage(call_hash).ok_or(Error::<T>::PreimageMissing)?;
+		let amendment = T::Amendment::decode(&mut &bytes[..]).map_err(|_| Error::<T>::PreimageInvalid)?;
+		ensure!(
+			amendment.get_dispatch_info().weight <= weight_bound,
+			Error::<T>::WeightBoundExceeded
+		);
+		Ok(amendment)
+	}
+
+	fn release_preimage(pending: &PendingAmendmentOf<T>) {
+		if pending.by_preimage {
+			T::Preimages::unrequest_preimage(&pending.call_hash);
+		}
+	}
+
+	/// Every amendment waiting for its security delay to expire, by id.
+	pub fn pending() -> Vec<(AmendmentId, PendingAmendmentOf<T>)> {
+		let mut pending: Vec<_> = <PendingAmendments<T>>::iter().collect();
+		pending.sort_by_key(|(id, _)| *id);
+		pending
+	}
+
+	/// Every vetoed amendment whose veto can still be overridden, with the block of the veto.
+	pub fn vetoed() -> Vec<(AmendmentId, PendingAmendmentOf<T>, T::BlockNumber)> {
+		let mut vetoed: Vec<_> = <VetoedAmendments<T>>::iter()
+			.map(|(id, (pending, vetoed_at))| (id, pending, vetoed_at))
+			.collect();
+		vetoed.sort_by_key(|(id, _, _)| *id);
+		vetoed
+	}
 }
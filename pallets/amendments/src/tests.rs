@@ -22,18 +22,21 @@ use sp_std::prelude::*;
 
 use codec::Encode;
 use frame_support::{
-	assert_noop, assert_ok, ord_parameter_types, parameter_types, traits::EqualPrivilegeOnly, weights::Weight,
+	assert_noop, assert_ok, ord_parameter_types, parameter_types,
+	traits::{EqualPrivilegeOnly, OnInitialize, PreimageProvider},
+	weights::{GetDispatchInfo, Weight},
 };
 use frame_system::{EnsureRoot, EnsureSignedBy};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, Hash, IdentityLookup},
 	DispatchError::BadOrigin,
 	Perbill,
 };
 
-use crate::{self as amendments, Config};
+use crate::{self as amendments, Config, DeclaredWrites, Error, PendingAmendment};
+use std::{cell::RefCell, collections::HashMap};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -100,11 +103,59 @@ impl pallet_scheduler::Config for Test {
 	type NoPreimagePostponement = ();
 }
 
+thread_local! {
+	static PREIMAGES: RefCell<HashMap<H256, Vec<u8>>> = RefCell::new(HashMap::new());
+	static REQUESTED: RefCell<HashMap<H256, u32>> = RefCell::new(HashMap::new());
+}
+
+/// Preimages kept in memory, along with how many times they were requested.
+pub struct TestPreimages;
+impl TestPreimages {
+	pub fn note(bytes: Vec<u8>) -> H256 {
+		let hash = BlakeTwo256::hash(&bytes);
+		PREIMAGES.with(|preimages| preimages.borrow_mut().insert(hash, bytes));
+		hash
+	}
+
+	pub fn requests(hash: &H256) -> u32 {
+		REQUESTED.with(|requested| requested.borrow().get(hash).copied().unwrap_or_default())
+	}
+}
+impl PreimageProvider<H256> for TestPreimages {
+	fn have_preimage(hash: &H256) -> bool {
+		PREIMAGES.with(|preimages| preimages.borrow().contains_key(hash))
+	}
+
+	fn get_preimage(hash: &H256) -> Option<Vec<u8>> {
+		PREIMAGES.with(|preimages| preimages.borrow().get(hash).cloned())
+	}
+
+	fn preimage_requested(hash: &H256) -> bool {
+		Self::requests(hash) > 0
+	}
+
+	fn request_preimage(hash: &H256) {
+		REQUESTED.with(|requested| *requested.borrow_mut().entry(*hash).or_default() += 1);
+	}
+
+	fn unrequest_preimage(hash: &H256) {
+		REQUESTED.with(|requested| {
+			let mut requested = requested.borrow_mut();
+			let count = requested.get_mut(hash).expect("unrequested a preimage never requested");
+			*count -= 1;
+		});
+	}
+}
+
 ord_parameter_types! {
 	pub const Proposer: u64 = 1;
 	pub const Veto: u64 = 2;
 	pub const Hacker: u64 = 3;
 	pub const BlockDelay: u64 = 10;
+	pub const FastTracker: u64 = 4;
+	pub const VetoOverrider: u64 = 5;
+	pub const FastTrackDelay: u64 = 2;
+	pub const VetoOverrideWindow: u64 = 5;
 }
 impl Config for Test {
 	type Event = ();
@@ -112,6 +163,11 @@ impl Config for Test {
 	type SubmissionOrigin = EnsureSignedBy<Proposer, u64>;
 	type VetoOrigin = EnsureSignedBy<Veto, u64>;
 	type Delay = BlockDelay;
+	type Preimages = TestPreimages;
+	type FastTrackOrigin = EnsureSignedBy<FastTracker, u64>;
+	type FastTrackDelay = FastTrackDelay;
+	type VetoOverrideOrigin = EnsureSignedBy<VetoOverrider, u64>;
+	type VetoOverrideWindow = VetoOverrideWindow;
 	type Scheduler = Scheduler;
 	type PalletsOrigin = OriginCaller;
 	type WeightInfo = ();
@@ -162,3 +218,388 @@ fn veto_proposal_before_delay_expired() {
 		assert_ok!(Amendments::veto(Origin::signed(Veto::get()), 0));
 	})
 }
+
+fn make_storage_proposal(value: u64) -> Box<Call> {
+	Box::new(Call::System(frame_system::Call::set_storage {
+		items: vec![(b"amended".to_vec(), value.encode())],
+	}))
+}
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+		Amendments::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn pending_amendments_are_recorded() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let proposal = make_proposal(1);
+		let call_hash = BlakeTwo256::hash_of(&proposal);
+		let weight_bound = proposal.get_dispatch_info().weight;
+		assert_ok!(Amendments::propose(Origin::signed(Proposer::get()), proposal));
+
+		assert_eq!(
+			Amendments::pending(),
+			vec![(
+				0,
+				PendingAmendment {
+					call_hash,
+					proposer: frame_system::RawOrigin::Signed(Proposer::get()).into(),
+					by_preimage: false,
+					weight_bound,
+					submitted_at: 3,
+					execute_at: 3 + BlockDelay::get(),
+				}
+			)]
+		);
+	})
+}
+
+#[test]
+fn amendments_are_executed_after_the_delay() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose(
+			Origin::signed(Proposer::get()),
+			make_storage_proposal(42)
+		));
+
+		run_to_block(BlockDelay::get() - 1);
+		assert_eq!(sp_io::storage::get(b"amended"), None);
+		assert!(Amendments::pending_amendments(0).is_some());
+
+		run_to_block(BlockDelay::get());
+		assert_eq!(sp_io::storage::get(b"amended"), Some(42u64.encode()));
+		assert_eq!(Amendments::pending(), vec![]);
+	})
+}
+
+#[test]
+fn vetoed_amendments_are_not_executed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose(
+			Origin::signed(Proposer::get()),
+			make_storage_proposal(42)
+		));
+		assert_ok!(Amendments::veto(Origin::signed(Veto::get()), 0));
+		assert_eq!(Amendments::pending(), vec![]);
+
+		run_to_block(BlockDelay::get());
+		assert_eq!(sp_io::storage::get(b"amended"), None);
+	})
+}
+
+#[test]
+fn execute_checks_the_pending_amendment() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose(Origin::signed(Proposer::get()), make_proposal(1)));
+
+		assert_noop!(
+			Amendments::execute(Origin::signed(Proposer::get()), 0, make_proposal(1)),
+			BadOrigin
+		);
+		assert_noop!(
+			Amendments::execute(Origin::root(), 1, make_proposal(1)),
+			Error::<Test>::UnknownAmendment
+		);
+		assert_noop!(
+			Amendments::execute(Origin::root(), 0, make_proposal(2)),
+			Error::<Test>::AmendmentMismatch
+		);
+		assert_ok!(Amendments::execute(Origin::root(), 0, make_proposal(1)));
+		assert_eq!(Amendments::pending_amendments(0), None);
+	})
+}
+
+#[test]
+fn non_authorized_origin_cannot_propose_preimage() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Amendments::propose_preimage(Origin::signed(Hacker::get()), H256::zero(), 1_000_000),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn preimage_amendments_are_fetched_when_executed() {
+	new_test_ext().execute_with(|| {
+		let call_hash = BlakeTwo256::hash_of(&make_storage_proposal(42));
+		assert_ok!(Amendments::propose_preimage(
+			Origin::signed(Proposer::get()),
+			call_hash,
+			1_000_000_000
+		));
+		assert_eq!(TestPreimages::requests(&call_hash), 1);
+		assert!(Amendments::pending_amendments(0).unwrap().by_preimage);
+
+		// the preimage only needs to be noted by the end of the delay
+		TestPreimages::note(make_storage_proposal(42).encode());
+		run_to_block(BlockDelay::get());
+		assert_eq!(sp_io::storage::get(b"amended"), Some(42u64.encode()));
+		assert_eq!(Amendments::pending(), vec![]);
+		assert_eq!(TestPreimages::requests(&call_hash), 0);
+	})
+}
+
+#[test]
+fn missing_preimages_drop_the_amendment() {
+	new_test_ext().execute_with(|| {
+		let call_hash = BlakeTwo256::hash_of(&make_storage_proposal(42));
+		assert_ok!(Amendments::propose_preimage(
+			Origin::signed(Proposer::get()),
+			call_hash,
+			1_000_000_000
+		));
+
+		run_to_block(BlockDelay::get());
+		assert_eq!(sp_io::storage::get(b"amended"), None);
+		assert_eq!(Amendments::pending(), vec![]);
+		assert_eq!(TestPreimages::requests(&call_hash), 0);
+
+		// noting the preimage later does not bring the amendment back
+		TestPreimages::note(make_storage_proposal(42).encode());
+		run_to_block(BlockDelay::get() * 2);
+		assert_eq!(sp_io::storage::get(b"amended"), None);
+	})
+}
+
+#[test]
+fn preimage_amendments_are_checked_before_execution() {
+	new_test_ext().execute_with(|| {
+		let invalid = TestPreimages::note(vec![0xff; 4]);
+		assert_ok!(Amendments::propose_preimage(
+			Origin::signed(Proposer::get()),
+			invalid,
+			1_000_000_000
+		));
+		let too_heavy = TestPreimages::note(make_storage_proposal(42).encode());
+		assert_ok!(Amendments::propose_preimage(
+			Origin::signed(Proposer::get()),
+			too_heavy,
+			1
+		));
+
+		// both are dropped, and the dispatch is still successful
+		assert_ok!(Amendments::execute_preimage(Origin::root(), 0, 1_000_000_000));
+		assert_ok!(Amendments::execute_preimage(Origin::root(), 1, 1));
+		assert_eq!(Amendments::pending(), vec![]);
+		assert_eq!(sp_io::storage::get(b"amended"), None);
+	})
+}
+
+#[test]
+fn final_vetoes_release_the_preimage() {
+	new_test_ext().execute_with(|| {
+		let call_hash = TestPreimages::note(make_storage_proposal(42).encode());
+		assert_ok!(Amendments::propose_preimage(
+			Origin::signed(Proposer::get()),
+			call_hash,
+			1_000_000_000
+		));
+		assert_ok!(Amendments::veto(Origin::signed(Veto::get()), 0));
+		// the veto may still be overridden
+		assert_eq!(TestPreimages::requests(&call_hash), 1);
+
+		run_to_block(VetoOverrideWindow::get() + 1);
+		assert_eq!(TestPreimages::requests(&call_hash), 0);
+		assert_eq!(Amendments::vetoed(), vec![]);
+
+		run_to_block(BlockDelay::get());
+		assert_eq!(sp_io::storage::get(b"amended"), None);
+	})
+}
+
+#[test]
+fn non_authorized_origin_cannot_fast_track() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose(Origin::signed(Proposer::get()), make_proposal(1)));
+
+		assert_noop!(
+			Amendments::propose_fast_track(Origin::signed(Proposer::get()), make_proposal(2)),
+			BadOrigin
+		);
+		assert_noop!(Amendments::fast_track(Origin::signed(Proposer::get()), 0), BadOrigin);
+	})
+}
+
+#[test]
+fn fast_tracked_amendments_use_the_shorter_delay() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose_fast_track(
+			Origin::signed(FastTracker::get()),
+			make_storage_proposal(42)
+		));
+		assert_eq!(
+			Amendments::pending_amendments(0).unwrap().execute_at,
+			FastTrackDelay::get()
+		);
+
+		run_to_block(FastTrackDelay::get());
+		assert_eq!(sp_io::storage::get(b"amended"), Some(42u64.encode()));
+	})
+}
+
+#[test]
+fn pending_amendments_can_be_fast_tracked() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose(
+			Origin::signed(Proposer::get()),
+			make_storage_proposal(42)
+		));
+		assert_noop!(
+			Amendments::fast_track(Origin::signed(FastTracker::get()), 1),
+			Error::<Test>::UnknownAmendment
+		);
+
+		run_to_block(3);
+		assert_ok!(Amendments::fast_track(Origin::signed(FastTracker::get()), 0));
+		assert_eq!(Amendments::pending_amendments(0).unwrap().execute_at, 5);
+
+		run_to_block(5);
+		assert_eq!(sp_io::storage::get(b"amended"), Some(42u64.encode()));
+	})
+}
+
+#[test]
+fn fast_tracking_never_delays_an_amendment() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose(Origin::signed(Proposer::get()), make_proposal(1)));
+
+		run_to_block(BlockDelay::get() - FastTrackDelay::get());
+		assert_noop!(
+			Amendments::fast_track(Origin::signed(FastTracker::get()), 0),
+			Error::<Test>::AlreadyDueSooner
+		);
+	})
+}
+
+#[test]
+fn vetoes_can_be_overridden_within_the_window() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose(
+			Origin::signed(Proposer::get()),
+			make_storage_proposal(42)
+		));
+		assert_ok!(Amendments::veto(Origin::signed(Veto::get()), 0));
+		assert_eq!(Amendments::vetoed().len(), 1);
+
+		assert_noop!(
+			Amendments::override_veto(Origin::signed(Veto::get()), 0, Some(make_storage_proposal(42))),
+			BadOrigin
+		);
+		assert_noop!(
+			Amendments::override_veto(Origin::signed(VetoOverrider::get()), 0, None),
+			Error::<Test>::AmendmentRequired
+		);
+		assert_noop!(
+			Amendments::override_veto(Origin::signed(VetoOverrider::get()), 0, Some(make_storage_proposal(43))),
+			Error::<Test>::AmendmentMismatch
+		);
+
+		run_to_block(VetoOverrideWindow::get());
+		assert_ok!(Amendments::override_veto(
+			Origin::signed(VetoOverrider::get()),
+			0,
+			Some(make_storage_proposal(42))
+		));
+		assert_eq!(Amendments::vetoed(), vec![]);
+		assert_eq!(Amendments::pending_amendments(0).unwrap().execute_at, BlockDelay::get());
+
+		run_to_block(BlockDelay::get());
+		assert_eq!(sp_io::storage::get(b"amended"), Some(42u64.encode()));
+	})
+}
+
+#[test]
+fn vetoes_can_not_be_overridden_after_the_window() {
+	new_test_ext().execute_with(|| {
+		let call_hash = TestPreimages::note(make_storage_proposal(42).encode());
+		assert_ok!(Amendments::propose_preimage(
+			Origin::signed(Proposer::get()),
+			call_hash,
+			1_000_000_000
+		));
+		assert_ok!(Amendments::veto(Origin::signed(Veto::get()), 0));
+
+		run_to_block(VetoOverrideWindow::get() + 1);
+		assert_noop!(
+			Amendments::override_veto(Origin::signed(VetoOverrider::get()), 0, None),
+			Error::<Test>::NotVetoed
+		);
+	})
+}
+
+#[test]
+fn overridden_vetoes_execute_at_the_next_block_if_overdue() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Amendments::propose_fast_track(
+			Origin::signed(FastTracker::get()),
+			make_storage_proposal(42)
+		));
+		assert_ok!(Amendments::veto(Origin::signed(Veto::get()), 0));
+
+		run_to_block(FastTrackDelay::get() + 1);
+		assert_ok!(Amendments::override_veto(
+			Origin::signed(VetoOverrider::get()),
+			0,
+			Some(make_storage_proposal(42))
+		));
+		assert_eq!(
+			Amendments::pending_amendments(0).unwrap().execute_at,
+			FastTrackDelay::get() + 2
+		);
+
+		// the expiry of the first veto does not finalize a second one
+		assert_ok!(Amendments::veto(Origin::signed(Veto::get()), 0));
+		run_to_block(VetoOverrideWindow::get() + 1);
+		assert_eq!(Amendments::vetoed().len(), 1);
+	})
+}
+
+#[test]
+fn dry_runs_do_not_keep_any_effect() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dry_run = Amendments::dry_run(&make_storage_proposal(42).encode()).unwrap();
+
+		assert_eq!(dry_run.result, Ok(()));
+		assert_eq!(
+			dry_run.declared_writes,
+			DeclaredWrites {
+				keys: vec![b"amended".to_vec()],
+				prefixes: vec![],
+			}
+		);
+		assert_eq!(sp_io::storage::get(b"amended"), None);
+	})
+}
+
+#[test]
+fn dry_runs_report_failures() {
+	new_test_ext().execute_with(|| {
+		let failing = Call::Amendments(crate::Call::execute {
+			amendment_id: 0,
+			amendment: make_proposal(1),
+		});
+		let dry_run = Amendments::dry_run(&failing.encode()).unwrap();
+		assert_eq!(dry_run.result, Err(Error::<Test>::UnknownAmendment.into()));
+		assert_eq!(dry_run.declared_writes, DeclaredWrites::default());
+
+		assert_noop!(Amendments::dry_run(&[0xff; 4]), Error::<Test>::UndecodableAmendment);
+	})
+}
+
+#[test]
+fn dry_runs_refuse_amendments_heavier_than_a_block() {
+	new_test_ext().execute_with(|| {
+		let heavy = Call::Amendments(crate::Call::execute_preimage {
+			amendment_id: 0,
+			weight_bound: Weight::MAX,
+		});
+		assert_noop!(Amendments::dry_run(&heavy.encode()), Error::<Test>::AmendmentTooHeavy);
+	})
+}
@@ -16,13 +16,13 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Autogenerated weights for pallet_amendments
+//! Weights for pallet_amendments
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2021-11-09, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//! NOT AUTO-GENERATED YET: these are hand-written placeholders, the storage accesses match
+//! the code but the base weights are estimates. Replace this file with the output of the
+//! command below once the benchmarks in `benchmarking.rs` ran on the reference hardware.
 
-// Executed Command:
+// Command to generate this file:
 // target/release/nodle-chain
 // benchmark
 // --chain=dev
@@ -49,33 +49,116 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn propose(b: u32) -> Weight;
 	fn veto() -> Weight;
+	fn execute() -> Weight;
+	fn propose_preimage() -> Weight;
+	fn execute_preimage() -> Weight;
+	fn propose_fast_track(b: u32) -> Weight;
+	fn fast_track() -> Weight;
+	fn override_veto() -> Weight;
+	fn on_initialize(v: u32) -> Weight;
 }
 
 /// Weights for pallet_amendments using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	fn propose(_b: u32) -> Weight {
-		(42_853_000 as Weight)
+		(45_218_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(7 as Weight))
-			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
 	}
 	fn veto() -> Weight {
-		(34_785_000 as Weight)
+		(36_104_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn execute() -> Weight {
+		(14_271_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn propose_preimage() -> Weight {
+		(47_630_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn execute_preimage() -> Weight {
+		(21_845_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn propose_fast_track(_b: u32) -> Weight {
+		(46_091_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn fast_track() -> Weight {
+		(31_582_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn override_veto() -> Weight {
+		(43_907_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(6 as Weight))
-			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn on_initialize(v: u32) -> Weight {
+		(3_104_000 as Weight)
+			.saturating_add((12_560_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
 	fn propose(_b: u32) -> Weight {
-		(42_853_000 as Weight)
+		(45_218_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
 	}
 	fn veto() -> Weight {
-		(34_785_000 as Weight)
+		(36_104_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn execute() -> Weight {
+		(14_271_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn propose_preimage() -> Weight {
+		(47_630_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn execute_preimage() -> Weight {
+		(21_845_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn propose_fast_track(_b: u32) -> Weight {
+		(46_091_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn fast_track() -> Weight {
+		(31_582_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn override_veto() -> Weight {
+		(43_907_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn on_initialize(v: u32) -> Weight {
+		(3_104_000 as Weight)
+			.saturating_add((12_560_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
 	}
 }
@@ -0,0 +1,47 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Runtime API definition for the emergency shutdown pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_emergency_shutdown::{Incident, IncidentAuthor, IncidentId, ShutdownReason, ShutdownScope};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait EmergencyShutdownApi<AccountId, BlockNumber, Scope, Reason> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Scope: Codec,
+		Reason: Codec,
+	{
+		/// Whether the whole shutdown is currently engaged.
+		fn is_shutdown() -> bool;
+
+		/// Incidents that were not lifted yet, explaining why the chain, or some of its
+		/// calls, are paused.
+		fn open_incidents() -> Vec<(IncidentId, Incident<AccountId, BlockNumber, Scope, Reason>)>;
+
+		/// Up to `count` incidents of the log, starting with `from`.
+		fn incident_log(
+			from: IncidentId,
+			count: u32,
+		) -> Vec<(IncidentId, Incident<AccountId, BlockNumber, Scope, Reason>)>;
+	}
+}
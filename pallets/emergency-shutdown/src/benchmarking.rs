@@ -28,9 +28,52 @@ use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
 use frame_support::traits::{EnsureOrigin, UnfilteredDispatchable};
 use sp_std::prelude::*;
 
+/// Pause of the transfers of `Balances`.
+fn transfer_scope<T: Config>() -> ShutdownScopeOf<T> {
+	let name = |name: &[u8]| -> BoundedVec<u8, T::MaxNameLength> { name.to_vec().try_into().unwrap() };
+	ShutdownScope::Call(name(b"Balances"), name(b"transfer"))
+}
+
 benchmarks! {
 	toggle {
-		let call = Call::<T>::toggle {};
+		let call = Call::<T>::toggle { reason: Some(ShutdownReason::SecurityIncident) };
+		let origin = T::ShutdownOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	shutdown_for {
+		let call = Call::<T>::shutdown_for { duration: 100u32.into(), reason: ShutdownReason::SecurityIncident };
+		let origin = T::ShutdownOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	extend_shutdown {
+		<Shutdown<T>>::put(true);
+		<ShutdownEndsAt<T>>::put(T::BlockNumber::from(100u32));
+		let call = Call::<T>::extend_shutdown { duration: 100u32.into() };
+		let origin = T::ShutdownOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	guardian_shutdown {
+		let call = Call::<T>::guardian_shutdown { scope: None, reason: ShutdownReason::SecurityIncident };
+		let origin = T::GuardianOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	pause {
+		let scope = transfer_scope::<T>();
+		let call = Call::<T>::pause { scope, reason: ShutdownReason::SecurityIncident };
+		let origin = T::ShutdownOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	unpause {
+		let scope = transfer_scope::<T>();
+		<Paused<T>>::insert(&scope, 0);
+		<Incidents<T>>::insert(0, Incident {
+			scope: Some(scope.clone()),
+			author: IncidentAuthor::Root,
+			reason: ShutdownReason::SecurityIncident,
+			engaged_at: T::BlockNumber::zero(),
+			lifted_at: None,
+		});
+		let call = Call::<T>::unpause { scope };
 		let origin = T::ShutdownOrigin::successful_origin();
 	}: { call.dispatch_bypass_filter(origin)? }
 
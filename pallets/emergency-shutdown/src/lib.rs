@@ -28,8 +28,107 @@ mod tests;
 pub mod weights;
 pub use weights::WeightInfo;
 
+use codec::{Decode, Encode};
+use frame_support::{
+	ensure,
+	traits::{CallMetadata, Contains, Get, GetCallMetadata, PalletInfoAccess},
+	BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchError, DispatchResult, RuntimeDebug,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
 pub use pallet::*;
 
+pub type ShutdownScopeOf<T> = ShutdownScope<<T as Config>::MaxNameLength>;
+pub type ShutdownReasonOf<T> = ShutdownReason<<T as Config>::MaxReasonLength>;
+pub type GuardianActionOf<T> = GuardianAction<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	ShutdownScopeOf<T>,
+>;
+
+/// A shutdown engaged by a guardian, without going through the committee.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct GuardianAction<AccountId, BlockNumber, Scope> {
+	/// Guardian who engaged the shutdown
+	pub guardian: AccountId,
+	/// Scope that was paused, or `None` for the whole shutdown
+	pub scope: Option<Scope>,
+	/// Block at which the shutdown was engaged
+	pub at: BlockNumber,
+}
+
+pub type IncidentId = u32;
+pub type IncidentOf<T> = Incident<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	ShutdownScopeOf<T>,
+	ShutdownReasonOf<T>,
+>;
+
+/// Why a shutdown was engaged, or a scope paused.
+#[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, scale_info::TypeInfo)]
+#[scale_info(skip_type_params(MaxLength))]
+pub enum ShutdownReason<MaxLength: Get<u32>> {
+	/// An exploit or a vulnerability is being mitigated
+	SecurityIncident,
+	/// Waiting on a runtime upgrade or a migration
+	Upgrade,
+	/// Planned maintenance
+	Maintenance,
+	/// Anything else, as a free form note of at most `MaxReasonLength` bytes
+	Other(BoundedVec<u8, MaxLength>),
+}
+
+/// Who engaged a shutdown, or paused a scope.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub enum IncidentAuthor<AccountId> {
+	/// The root origin
+	Root,
+	/// The `ShutdownOrigin` of the runtime, usually a collective
+	ShutdownOrigin,
+	/// A single guardian
+	Guardian(AccountId),
+}
+
+/// Entry of the incident log, covering a shutdown or the pause of a scope from the moment it
+/// was engaged until it was lifted.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct Incident<AccountId, BlockNumber, Scope, Reason> {
+	/// Scope that was paused, or `None` for the whole shutdown
+	pub scope: Option<Scope>,
+	/// Who engaged it
+	pub author: IncidentAuthor<AccountId>,
+	/// Why it was engaged
+	pub reason: Reason,
+	/// Block at which it was engaged
+	pub engaged_at: BlockNumber,
+	/// Block at which it was lifted, if it was
+	pub lifted_at: Option<BlockNumber>,
+}
+
+/// Part of the runtime that can be paused on its own, names are at most `MaxNameLength` bytes.
+#[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, scale_info::TypeInfo)]
+#[scale_info(skip_type_params(MaxLength))]
+pub enum ShutdownScope<MaxLength: Get<u32>> {
+	/// All the calls of a pallet, by its name in the runtime
+	Pallet(BoundedVec<u8, MaxLength>),
+	/// A single call, by the name of its pallet in the runtime and its own name
+	Call(BoundedVec<u8, MaxLength>, BoundedVec<u8, MaxLength>),
+}
+
+impl<MaxLength: Get<u32>> ShutdownScope<MaxLength> {
+	/// Name of the pallet the scope belongs to.
+	pub fn pallet_name(&self) -> &[u8] {
+		match self {
+			ShutdownScope::Pallet(pallet) | ShutdownScope::Call(pallet, _) => pallet,
+		}
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -40,6 +139,21 @@ pub mod pallet {
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type ShutdownOrigin: EnsureOrigin<Self::Origin>;
+		/// Can engage a shutdown, or pause a scope, instantly. Lifting it still requires
+		/// `ShutdownOrigin`.
+		type GuardianOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+		/// Scopes a guardian may pause on its own, on top of `PausablePallets`.
+		type GuardianScopes: Contains<ShutdownScope<Self::MaxNameLength>>;
+		/// Maximum length, in bytes, of a `ShutdownReason::Other` note.
+		#[pallet::constant]
+		type MaxReasonLength: Get<u32>;
+		/// Maximum length, in bytes, of the pallet and call names of a `ShutdownScope`.
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+		/// Pallets, by their name in the runtime, whose calls may be paused. The filter also
+		/// applies to inherents, so the pallets needed to author blocks or to lift a pause must
+		/// be left out. This pallet can never be paused.
+		type PausablePallets: Contains<Vec<u8>>;
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -50,17 +164,127 @@ pub mod pallet {
 	pub struct Pallet<T>(PhantomData<T>);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			match Self::shutdown_ends_at() {
+				Some(ends_at) if n >= ends_at => {
+					Self::do_lift();
+					Self::deposit_event(Event::ShutdownExpired);
+
+					T::DbWeight::get().reads_writes(3, 4)
+				}
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Toggle the shutdown state if authorized to do so.
+		/// Pause every call covered by `scope`, through the `ShutdownFilter` of the runtime.
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(
+			origin: OriginFor<T>,
+			scope: ShutdownScopeOf<T>,
+			reason: ShutdownReasonOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let author = Self::ensure_shutdown_origin(origin)?;
+			Self::do_pause(author, scope, reason)?;
+
+			Ok(().into())
+		}
+
+		/// Resume the calls covered by `scope`.
+		#[pallet::weight(T::WeightInfo::unpause())]
+		pub fn unpause(origin: OriginFor<T>, scope: ShutdownScopeOf<T>) -> DispatchResultWithPostInfo {
+			T::ShutdownOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			let incident = <Paused<T>>::take(&scope).ok_or(Error::<T>::NotPaused)?;
+
+			Self::close_incident(incident);
+			Self::deposit_event(Event::ScopeUnpaused(scope));
+
+			Ok(().into())
+		}
+
+		/// Toggle the shutdown state if authorized to do so. Engaging the shutdown requires a
+		/// `reason`, which is ignored when lifting it. Lifting a shutdown started with
+		/// `shutdown_for` ends it early.
 		#[pallet::weight(T::WeightInfo::toggle())]
-		pub fn toggle(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+		pub fn toggle(origin: OriginFor<T>, reason: Option<ShutdownReasonOf<T>>) -> DispatchResultWithPostInfo {
+			let author = Self::ensure_shutdown_origin(origin)?;
+
+			if Self::shutdown() {
+				Self::do_lift();
+				Self::deposit_event(Event::ShutdownToggled(false));
+			} else {
+				Self::do_engage(author, reason.ok_or(Error::<T>::ReasonRequired)?)?;
+			}
+
+			Ok(().into())
+		}
+
+		/// Engage the shutdown, or pause `scope` if any, on behalf of a guardian. Every use
+		/// is recorded in `GuardianActions`.
+		#[pallet::weight(T::WeightInfo::guardian_shutdown())]
+		pub fn guardian_shutdown(
+			origin: OriginFor<T>,
+			scope: Option<ShutdownScopeOf<T>>,
+			reason: ShutdownReasonOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let guardian = T::GuardianOrigin::ensure_origin(origin)?;
+
+			if let Some(ref scope) = scope {
+				ensure!(T::GuardianScopes::contains(scope), Error::<T>::NotGuardianScope);
+			}
+
+			let author = IncidentAuthor::Guardian(guardian.clone());
+			match scope {
+				Some(ref scope) => Self::do_pause(author, scope.clone(), reason)?,
+				None => Self::do_engage(author, reason)?,
+			}
+
+			let index = Self::guardian_action_count();
+			<GuardianActions<T>>::insert(
+				index,
+				GuardianAction {
+					guardian: guardian.clone(),
+					scope: scope.clone(),
+					at: frame_system::Pallet::<T>::block_number(),
+				},
+			);
+			<GuardianActionCount<T>>::put(index.saturating_add(1));
+			Self::deposit_event(Event::GuardianShutdown(guardian, scope));
+
+			Ok(().into())
+		}
+
+		/// Engage the shutdown for `duration` blocks, after which it is lifted automatically.
+		#[pallet::weight(T::WeightInfo::shutdown_for())]
+		pub fn shutdown_for(
+			origin: OriginFor<T>,
+			duration: T::BlockNumber,
+			reason: ShutdownReasonOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let author = Self::ensure_shutdown_origin(origin)?;
+			ensure!(!duration.is_zero(), Error::<T>::ZeroDuration);
+
+			Self::do_engage(author, reason)?;
+			let ends_at = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+			<ShutdownEndsAt<T>>::put(ends_at);
+			Self::deposit_event(Event::ShutdownEndSet(ends_at));
+
+			Ok(().into())
+		}
+
+		/// Push the end of a shutdown started with `shutdown_for` by `duration` blocks.
+		#[pallet::weight(T::WeightInfo::extend_shutdown())]
+		pub fn extend_shutdown(origin: OriginFor<T>, duration: T::BlockNumber) -> DispatchResultWithPostInfo {
 			T::ShutdownOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
 
-			<Shutdown<T>>::put(!Self::shutdown());
-			Self::deposit_event(Event::ShutdownToggled(Self::shutdown()));
+			let ends_at = Self::shutdown_ends_at()
+				.ok_or(Error::<T>::NotTimeBoxed)?
+				.saturating_add(duration);
+			<ShutdownEndsAt<T>>::put(ends_at);
+			Self::deposit_event(Event::ShutdownEndSet(ends_at));
 
 			Ok(().into())
 		}
@@ -71,9 +295,211 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// Shutdown state was toggled, to either on or off.
 		ShutdownToggled(bool),
+		/// The shutdown will be lifted automatically at this block \[ends_at\]
+		ShutdownEndSet(T::BlockNumber),
+		/// A time boxed shutdown reached its end and was lifted
+		ShutdownExpired,
+		/// A guardian engaged the shutdown, or paused a scope \[guardian, scope\]
+		GuardianShutdown(T::AccountId, Option<ShutdownScopeOf<T>>),
+		/// The calls of a scope were paused \[scope\]
+		ScopePaused(ShutdownScopeOf<T>),
+		/// The calls of a scope were resumed \[scope\]
+		ScopeUnpaused(ShutdownScopeOf<T>),
+		/// A shutdown was engaged, or a scope paused, and logged \[incident, reason\]
+		IncidentOpened(IncidentId, ShutdownReasonOf<T>),
+		/// The shutdown or pause logged by this incident was lifted \[incident\]
+		IncidentClosed(IncidentId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// This scope is already paused
+		AlreadyPaused,
+		/// This scope is not paused
+		NotPaused,
+		/// The calls of this pallet can not be paused
+		CannotPauseSelf,
+		/// The calls of this pallet are not in `PausablePallets`
+		NotPausable,
+		/// Guardians may not pause this scope
+		NotGuardianScope,
+		/// The shutdown is already engaged
+		AlreadyShutdown,
+		/// A shutdown can not last zero blocks
+		ZeroDuration,
+		/// The shutdown was not started with an end
+		NotTimeBoxed,
+		/// A reason must be given when engaging the shutdown
+		ReasonRequired,
 	}
 
 	#[pallet::storage]
 	#[pallet::getter(fn shutdown)]
 	pub type Shutdown<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Block at which the current shutdown is lifted, if it was started with an end
+	#[pallet::storage]
+	#[pallet::getter(fn shutdown_ends_at)]
+	pub type ShutdownEndsAt<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Number of guardian actions recorded so far
+	#[pallet::storage]
+	#[pallet::getter(fn guardian_action_count)]
+	pub type GuardianActionCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Every shutdown engaged by a guardian, by order of use
+	#[pallet::storage]
+	#[pallet::getter(fn guardian_actions)]
+	pub type GuardianActions<T: Config> = StorageMap<_, Twox64Concat, u32, GuardianActionOf<T>, OptionQuery>;
+
+	/// Scopes whose calls are currently paused, along with the incident that paused them
+	#[pallet::storage]
+	#[pallet::getter(fn paused)]
+	pub type Paused<T: Config> = StorageMap<_, Blake2_128Concat, ShutdownScopeOf<T>, IncidentId, OptionQuery>;
+
+	/// Incident logged when the current shutdown was engaged
+	#[pallet::storage]
+	#[pallet::getter(fn shutdown_incident)]
+	pub type ShutdownIncident<T: Config> = StorageValue<_, IncidentId, OptionQuery>;
+
+	/// Number of incidents logged so far
+	#[pallet::storage]
+	#[pallet::getter(fn incident_count)]
+	pub type IncidentCount<T: Config> = StorageValue<_, IncidentId, ValueQuery>;
+
+	/// Every shutdown engaged and scope paused, by order of occurrence
+	#[pallet::storage]
+	#[pallet::getter(fn incident)]
+	pub type Incidents<T: Config> = StorageMap<_, Twox64Concat, IncidentId, IncidentOf<T>, OptionQuery>;
+}
+
+impl<T: Config> Pallet<T> {
+	fn ensure_shutdown_origin(origin: T::Origin) -> Result<IncidentAuthor<T::AccountId>, DispatchError> {
+		match T::ShutdownOrigin::try_origin(origin) {
+			Ok(_) => Ok(IncidentAuthor::ShutdownOrigin),
+			Err(origin) => frame_system::ensure_root(origin)
+				.map(|_| IncidentAuthor::Root)
+				.map_err(Into::into),
+		}
+	}
+
+	fn do_pause(
+		author: IncidentAuthor<T::AccountId>,
+		scope: ShutdownScopeOf<T>,
+		reason: ShutdownReasonOf<T>,
+	) -> DispatchResult {
+		// pausing ourselves would leave no way to unpause anything
+		ensure!(
+			scope.pallet_name() != <Self as PalletInfoAccess>::name().as_bytes(),
+			Error::<T>::CannotPauseSelf
+		);
+		ensure!(
+			T::PausablePallets::contains(&scope.pallet_name().to_vec()),
+			Error::<T>::NotPausable
+		);
+		ensure!(!<Paused<T>>::contains_key(&scope), Error::<T>::AlreadyPaused);
+
+		let incident = Self::open_incident(author, Some(scope.clone()), reason)?;
+		<Paused<T>>::insert(&scope, incident);
+		Self::deposit_event(Event::ScopePaused(scope));
+		Ok(())
+	}
+
+	fn do_engage(author: IncidentAuthor<T::AccountId>, reason: ShutdownReasonOf<T>) -> DispatchResult {
+		ensure!(!Self::shutdown(), Error::<T>::AlreadyShutdown);
+
+		let incident = Self::open_incident(author, None, reason)?;
+		<Shutdown<T>>::put(true);
+		<ShutdownIncident<T>>::put(incident);
+		Self::deposit_event(Event::ShutdownToggled(true));
+		Ok(())
+	}
+
+	fn do_lift() {
+		<Shutdown<T>>::put(false);
+		<ShutdownEndsAt<T>>::kill();
+		if let Some(incident) = <ShutdownIncident<T>>::take() {
+			Self::close_incident(incident);
+		}
+	}
+
+	fn open_incident(
+		author: IncidentAuthor<T::AccountId>,
+		scope: Option<ShutdownScopeOf<T>>,
+		reason: ShutdownReasonOf<T>,
+	) -> Result<IncidentId, DispatchError> {
+		let id = Self::incident_count();
+		<Incidents<T>>::insert(
+			id,
+			Incident {
+				scope,
+				author,
+				reason: reason.clone(),
+				engaged_at: frame_system::Pallet::<T>::block_number(),
+				lifted_at: None,
+			},
+		);
+		<IncidentCount<T>>::put(id.saturating_add(1));
+		Self::deposit_event(Event::IncidentOpened(id, reason));
+		Ok(id)
+	}
+
+	fn close_incident(id: IncidentId) {
+		<Incidents<T>>::mutate(id, |maybe_incident| {
+			if let Some(incident) = maybe_incident {
+				incident.lifted_at = Some(frame_system::Pallet::<T>::block_number());
+			}
+		});
+		Self::deposit_event(Event::IncidentClosed(id));
+	}
+
+	/// Incidents that were not lifted yet, for the shutdown and every paused scope.
+	pub fn open_incidents() -> Vec<(IncidentId, IncidentOf<T>)> {
+		Self::shutdown_incident()
+			.into_iter()
+			.chain(<Paused<T>>::iter_values())
+			.filter_map(|id| Self::incident(id).map(|incident| (id, incident)))
+			.collect()
+	}
+
+	/// Up to `count` incidents of the log, starting with `from`.
+	pub fn incident_log(from: IncidentId, count: u32) -> Vec<(IncidentId, IncidentOf<T>)> {
+		let end = from.saturating_add(count).min(Self::incident_count());
+		(from..end)
+			.filter_map(|id| Self::incident(id).map(|incident| (id, incident)))
+			.collect()
+	}
+
+	/// Whether the call `call_name` of the pallet `pallet_name` is paused, either on its own
+	/// or along with its whole pallet.
+	pub fn is_paused(pallet_name: &[u8], call_name: &[u8]) -> bool {
+		// names longer than `MaxNameLength` can not have been paused
+		let pallet = match BoundedVec::try_from(pallet_name.to_vec()) {
+			Ok(pallet) => pallet,
+			Err(_) => return false,
+		};
+		if <Paused<T>>::contains_key(ShutdownScope::Pallet(pallet.clone())) {
+			return true;
+		}
+		BoundedVec::try_from(call_name.to_vec()).map_or(false, |call| {
+			<Paused<T>>::contains_key(ShutdownScope::Call(pallet, call))
+		})
+	}
+}
+
+/// Call filter rejecting the calls that are currently paused, meant to be used as, or as part
+/// of, the `BaseCallFilter` of the runtime.
+pub struct ShutdownFilter<T>(PhantomData<T>);
+
+impl<T: Config> Contains<T::Call> for ShutdownFilter<T>
+where
+	T::Call: GetCallMetadata,
+{
+	fn contains(call: &T::Call) -> bool {
+		let CallMetadata {
+			pallet_name,
+			function_name,
+		} = call.get_call_metadata();
+		!Pallet::<T>::is_paused(pallet_name.as_bytes(), function_name.as_bytes())
+	}
 }
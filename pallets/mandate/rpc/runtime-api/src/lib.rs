@@ -0,0 +1,38 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Runtime API definition for the mandate pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_mandate::{MandateExecution, MandateId, ScheduledMandate};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait MandateApi<Hash, BlockNumber> where
+		Hash: Codec,
+		BlockNumber: Codec,
+	{
+		/// Every call waiting to be dispatched by the scheduler, by id.
+		fn scheduled_mandates() -> Vec<(MandateId, ScheduledMandate<Hash, BlockNumber>)>;
+
+		/// Latest executions of the mandate, oldest first.
+		fn mandate_history() -> Vec<MandateExecution<Hash, BlockNumber>>;
+	}
+}
@@ -0,0 +1,112 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Mandate pallet benchmarks
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use crate::Pallet as Mandate;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::{EnsureOrigin, Get, UnfilteredDispatchable};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Hash;
+use sp_std::prelude::*;
+
+/// A call the mandate is allowed to dispatch.
+fn allowed_call<T: Config>() -> <T as Config>::Call
+where
+	<T as Config>::Call: From<frame_system::Call<T>>,
+{
+	let call: <T as Config>::Call = frame_system::Call::<T>::remark { remark: vec![] }.into();
+	AllowedCalls::<T>::insert(Mandate::<T>::call_indices(&call), ());
+	call
+}
+
+/// Fill the history so that recording an execution also drops the oldest one.
+fn fill_history<T: Config>() {
+	let execution = MandateExecution {
+		call_hash: T::Hash::default(),
+		block: T::BlockNumber::default(),
+		result: Ok(()),
+	};
+	History::<T>::put(vec![execution; T::MaxHistoryLength::get() as usize]);
+}
+
+benchmarks! {
+	where_clause { where <T as Config>::Call: From<frame_system::Call<T>> }
+
+	apply {
+		fill_history::<T>();
+		let call = Call::<T>::apply { call: Box::new(allowed_call::<T>()) };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	batch_apply {
+		let c in 1 .. T::MaxBatchedCalls::get();
+
+		fill_history::<T>();
+		let calls = (0..c).map(|_| allowed_call::<T>()).collect::<Vec<_>>().try_into().unwrap();
+		let call = Call::<T>::batch_apply { calls };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	apply_at {
+		let call = Call::<T>::apply_at { when: 10u32.into(), call: Box::new(allowed_call::<T>()) };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	apply_after {
+		let call = Call::<T>::apply_after { delay: 10u32.into(), call: Box::new(allowed_call::<T>()) };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	cancel_scheduled {
+		Call::<T>::apply_after { delay: 10u32.into(), call: Box::new(allowed_call::<T>()) }
+			.dispatch_bypass_filter(T::ExternalOrigin::successful_origin())?;
+		let call = Call::<T>::cancel_scheduled { mandate_id: 0 };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	execute_scheduled {
+		fill_history::<T>();
+		let call = allowed_call::<T>();
+		ScheduledMandates::<T>::insert(0, ScheduledMandate {
+			call_hash: T::Hashing::hash_of(&call),
+			when: T::BlockNumber::default(),
+		});
+	}: _(RawOrigin::Root, 0, Box::new(call))
+
+	allow_call {
+		let call = Call::<T>::allow_call { pallet_index: 0, call_index: 0 };
+		let origin = T::AllowlistOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	disallow_call {
+		AllowedCalls::<T>::insert((0, 0), ());
+		let call = Call::<T>::disallow_call { pallet_index: 0, call_index: 0 };
+		let origin = T::AllowlistOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	impl_benchmark_test_suite!(
+		Mandate,
+		crate::tests::new_test_ext(),
+		crate::tests::Test,
+	);
+}
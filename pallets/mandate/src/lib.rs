@@ -21,32 +21,107 @@
 //! Handle the ability to notify other pallets that they should stop all
 //! operations, or resume them
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+#[cfg(test)]
+mod tests;
+
+use codec::{Decode, Encode};
+use frame_support::traits::LockIdentifier;
+use sp_runtime::{DispatchError, DispatchResult, RuntimeDebug};
+
+pub mod weights;
+pub use weights::WeightInfo;
+
 pub use pallet::*;
 
+const MANDATE_ID: LockIdentifier = *b"mandates";
+
+/// Identifier of a scheduled mandate, unique over the lifetime of the pallet.
+pub type MandateId = u64;
+
+/// A call dispatched by the mandate, kept in the execution history.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct MandateExecution<Hash, BlockNumber> {
+	/// Hash of the call, or of all the calls of a batch
+	pub call_hash: Hash,
+	/// Block the call was dispatched at
+	pub block: BlockNumber,
+	/// Result of the dispatch
+	pub result: DispatchResult,
+}
+
+pub type MandateExecutionOf<T> =
+	MandateExecution<<T as frame_system::Config>::Hash, <T as frame_system::Config>::BlockNumber>;
+
+/// A call the mandate will dispatch at a given block.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct ScheduledMandate<Hash, BlockNumber> {
+	/// Hash of the call
+	pub call_hash: Hash,
+	/// Block the call will be dispatched at
+	pub when: BlockNumber,
+}
+
+pub type ScheduledMandateOf<T> =
+	ScheduledMandate<<T as frame_system::Config>::Hash, <T as frame_system::Config>::BlockNumber>;
+
 #[frame_support::pallet]
 pub mod pallet {
+	use super::*;
 	use frame_support::{
 		pallet_prelude::*,
-		traits::{EnsureOrigin, UnfilteredDispatchable},
+		storage::{with_transaction, TransactionOutcome},
+		traits::{
+			schedule::{DispatchTime, Named as ScheduleNamed},
+			Contains, EnsureOrigin, UnfilteredDispatchable,
+		},
 		weights::GetDispatchInfo,
 		Parameter,
 	};
 	use frame_system::pallet_prelude::*;
-	use sp_runtime::DispatchResult;
-	use sp_std::prelude::Box;
+	use sp_runtime::traits::{Hash, Saturating};
+	use sp_std::prelude::*;
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
-		type Call: Parameter + UnfilteredDispatchable<Origin = Self::Origin> + GetDispatchInfo;
+		type Call: Parameter + UnfilteredDispatchable<Origin = Self::Origin> + GetDispatchInfo + From<Call<Self>>;
+
+		/// Used to dispatch the calls scheduled with `apply_at` or `apply_after`.
+		type Scheduler: ScheduleNamed<Self::BlockNumber, <Self as Config>::Call, Self::PalletsOrigin>;
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
 
 		/// Origin that can call this module and execute sudo actions. Typically
 		/// the `collective` module.
 		type ExternalOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Origin that can change the calls the mandate may dispatch. No call is allowed at
+		/// genesis, chains upgrading from an unrestricted mandate seed the allowlist with
+		/// `migrations::v1::SeedAllowedCalls`.
+		type AllowlistOrigin: EnsureOrigin<Self::Origin>;
+
+		/// How many executions are kept in the history.
+		#[pallet::constant]
+		type MaxHistoryLength: Get<u32>;
+
+		/// Maximum number of calls dispatched by a single `batch_apply`.
+		#[pallet::constant]
+		type MaxBatchedCalls: Get<u32>;
+
+		/// Calls dispatching other calls, such as batches, multisigs or the scheduler. The
+		/// mandate never dispatches them, whatever the allowlist, as the calls they wrap would
+		/// escape it.
+		type CallWrappers: Contains<<Self as Config>::Call>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(migrations::v1::STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(PhantomData<T>);
 
@@ -55,14 +130,165 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Let the configured origin dispatch a call as root
-		#[pallet::weight(call.get_dispatch_info().weight.saturating_add(10_000))]
+		/// Let the configured origin dispatch a call as root, if it is in the allowlist
+		#[pallet::weight(T::WeightInfo::apply().saturating_add(call.get_dispatch_info().weight))]
 		pub fn apply(origin: OriginFor<T>, call: Box<<T as Config>::Call>) -> DispatchResultWithPostInfo {
 			T::ExternalOrigin::ensure_origin(origin)?;
+			Self::ensure_allowed(&call)?;
 
+			let call_hash = T::Hashing::hash_of(&call);
 			// Shamelessly stollen from the `sudo` module
 			let res = call.dispatch_bypass_filter(frame_system::RawOrigin::Root.into());
-			Self::deposit_event(Event::RootOp(res.map(|_| ()).map_err(|e| e.error)));
+			let res = res.map(|_| ()).map_err(|e| e.error);
+			Self::record(call_hash, res);
+			Self::deposit_event(Event::RootOp(res));
+
+			Ok(().into())
+		}
+
+		/// Let the configured origin dispatch up to `MaxBatchedCalls` calls as root, if they are
+		/// all in the allowlist. Either all of them succeed, or none of their changes are kept.
+		#[pallet::weight(
+			calls.iter()
+				.map(|call| call.get_dispatch_info().weight)
+				.fold(T::WeightInfo::batch_apply(calls.len() as u32), |total: Weight, weight| {
+					total.saturating_add(weight)
+				})
+		)]
+		pub fn batch_apply(
+			origin: OriginFor<T>,
+			calls: BoundedVec<<T as Config>::Call, T::MaxBatchedCalls>,
+		) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::ensure_origin(origin)?;
+			for call in calls.iter() {
+				Self::ensure_allowed(call)?;
+			}
+
+			let call_hash = T::Hashing::hash_of(&calls);
+			let interrupted = with_transaction(|| -> TransactionOutcome<Result<_, DispatchError>> {
+				for (index, call) in calls.into_iter().enumerate() {
+					if let Err(e) = call.dispatch_bypass_filter(frame_system::RawOrigin::Root.into()) {
+						return TransactionOutcome::Rollback(Ok(Some((index as u32, e.error))));
+					}
+				}
+				TransactionOutcome::Commit(Ok(None))
+			})?;
+
+			match interrupted {
+				None => {
+					Self::record(call_hash, Ok(()));
+					Self::deposit_event(Event::BatchCompleted);
+				}
+				Some((index, error)) => {
+					Self::record(call_hash, Err(error));
+					Self::deposit_event(Event::BatchInterrupted(index, error));
+				}
+			}
+
+			Ok(().into())
+		}
+
+		/// Let the configured origin schedule a call to be dispatched as root at block `when`,
+		/// if it is still in the allowlist by then
+		#[pallet::weight(T::WeightInfo::apply_at())]
+		pub fn apply_at(
+			origin: OriginFor<T>,
+			when: T::BlockNumber,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::ensure_origin(origin)?;
+			Self::ensure_allowed(&call)?;
+			Self::schedule(when, call)?;
+
+			Ok(().into())
+		}
+
+		/// Let the configured origin schedule a call to be dispatched as root in `delay` blocks,
+		/// if it is still in the allowlist by then
+		#[pallet::weight(T::WeightInfo::apply_after())]
+		pub fn apply_after(
+			origin: OriginFor<T>,
+			delay: T::BlockNumber,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::ensure_origin(origin)?;
+			Self::ensure_allowed(&call)?;
+			let when = frame_system::Pallet::<T>::block_number().saturating_add(delay);
+			Self::schedule(when, call)?;
+
+			Ok(().into())
+		}
+
+		/// Let the configured origin cancel a call scheduled with `apply_at` or `apply_after`
+		#[pallet::weight(T::WeightInfo::cancel_scheduled())]
+		pub fn cancel_scheduled(origin: OriginFor<T>, mandate_id: MandateId) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::ensure_origin(origin)?;
+			ensure!(
+				ScheduledMandates::<T>::contains_key(mandate_id),
+				Error::<T>::UnknownMandate
+			);
+
+			if T::Scheduler::cancel_named((MANDATE_ID, mandate_id).encode()).is_err() {
+				return Err(Error::<T>::FailedToCancel.into());
+			}
+			ScheduledMandates::<T>::remove(mandate_id);
+			Self::deposit_event(Event::ScheduledMandateCancelled(mandate_id));
+
+			Ok(().into())
+		}
+
+		/// Dispatch a call scheduled with `apply_at` or `apply_after`, this is dispatched by
+		/// the scheduler.
+		#[pallet::weight(T::WeightInfo::execute_scheduled().saturating_add(call.get_dispatch_info().weight))]
+		pub fn execute_scheduled(
+			origin: OriginFor<T>,
+			mandate_id: MandateId,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let scheduled = ScheduledMandates::<T>::get(mandate_id).ok_or(Error::<T>::UnknownMandate)?;
+			let call_hash = T::Hashing::hash_of(&call);
+			ensure!(scheduled.call_hash == call_hash, Error::<T>::MandateMismatch);
+
+			ScheduledMandates::<T>::remove(mandate_id);
+			// the allowlist may have changed since the call was scheduled
+			let res = Self::ensure_allowed(&call).and_then(|_| {
+				call.dispatch_bypass_filter(frame_system::RawOrigin::Root.into())
+					.map(|_| ())
+					.map_err(|e| e.error)
+			});
+			Self::record(call_hash, res);
+			Self::deposit_event(Event::ScheduledMandateExecuted(mandate_id, res));
+
+			Ok(().into())
+		}
+
+		/// Allow the mandate to dispatch the call `call_index` of the pallet `pallet_index`
+		#[pallet::weight(T::WeightInfo::allow_call())]
+		pub fn allow_call(origin: OriginFor<T>, pallet_index: u8, call_index: u8) -> DispatchResultWithPostInfo {
+			T::AllowlistOrigin::ensure_origin(origin)?;
+			ensure!(
+				!AllowedCalls::<T>::contains_key((pallet_index, call_index)),
+				Error::<T>::AlreadyAllowed
+			);
+
+			AllowedCalls::<T>::insert((pallet_index, call_index), ());
+			Self::deposit_event(Event::CallAllowed(pallet_index, call_index));
+
+			Ok(().into())
+		}
+
+		/// Stop the mandate from dispatching the call `call_index` of the pallet `pallet_index`
+		#[pallet::weight(T::WeightInfo::disallow_call())]
+		pub fn disallow_call(origin: OriginFor<T>, pallet_index: u8, call_index: u8) -> DispatchResultWithPostInfo {
+			T::AllowlistOrigin::ensure_origin(origin)?;
+			ensure!(
+				AllowedCalls::<T>::contains_key((pallet_index, call_index)),
+				Error::<T>::NotAllowed
+			);
+
+			AllowedCalls::<T>::remove((pallet_index, call_index));
+			Self::deposit_event(Event::CallDisallowed(pallet_index, call_index));
 
 			Ok(().into())
 		}
@@ -73,5 +299,129 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// A root operation was executed, show result
 		RootOp(DispatchResult),
+		/// All the calls of a batch were executed
+		BatchCompleted,
+		/// A call of a batch failed, none of the calls of the batch were kept \[index, error\]
+		BatchInterrupted(u32, DispatchError),
+		/// The mandate may now dispatch a call \[pallet_index, call_index\]
+		CallAllowed(u8, u8),
+		/// The mandate may no longer dispatch a call \[pallet_index, call_index\]
+		CallDisallowed(u8, u8),
+		/// A call was scheduled to be dispatched at the given block \[mandate_id, when\]
+		MandateScheduled(MandateId, T::BlockNumber),
+		/// A scheduled call was cancelled \[mandate_id\]
+		ScheduledMandateCancelled(MandateId),
+		/// A scheduled call was dispatched, show result \[mandate_id, result\]
+		ScheduledMandateExecuted(MandateId, DispatchResult),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The call is not in the allowlist
+		CallNotAllowed,
+		/// The call is already in the allowlist
+		AlreadyAllowed,
+		/// The call is not in the allowlist
+		NotAllowed,
+		/// Calls can only be scheduled for a future block
+		ScheduleInPast,
+		/// We failed to schedule the call
+		FailedToSchedule,
+		/// We failed to cancel the scheduled call
+		FailedToCancel,
+		/// No scheduled call has this id
+		UnknownMandate,
+		/// The call does not match the one that was scheduled
+		MandateMismatch,
+		/// The call dispatches other calls, which would escape the allowlist
+		CallWrapperNotAllowed,
+	}
+
+	/// Calls the mandate may dispatch, by pallet and call index
+	#[pallet::storage]
+	#[pallet::getter(fn allowed_calls)]
+	pub type AllowedCalls<T: Config> = StorageMap<_, Twox64Concat, (u8, u8), (), OptionQuery>;
+
+	/// Latest executions of the mandate, oldest first
+	#[pallet::storage]
+	#[pallet::getter(fn history)]
+	pub type History<T: Config> = StorageValue<_, Vec<MandateExecutionOf<T>>, ValueQuery>;
+
+	/// Calls waiting to be dispatched by the scheduler
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_mandates)]
+	pub type ScheduledMandates<T: Config> = StorageMap<_, Twox64Concat, MandateId, ScheduledMandateOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn mandates_scheduled)]
+	pub type MandatesScheduled<T: Config> = StorageValue<_, MandateId, ValueQuery>;
+
+	impl<T: Config> Pallet<T> {
+		/// Pallet and call indices of `call`, as used by the allowlist.
+		pub fn call_indices(call: &<T as Config>::Call) -> (u8, u8) {
+			call.using_encoded(|encoded| {
+				(
+					encoded.first().copied().unwrap_or_default(),
+					encoded.get(1).copied().unwrap_or_default(),
+				)
+			})
+		}
+
+		fn ensure_allowed(call: &<T as Config>::Call) -> DispatchResult {
+			ensure!(!T::CallWrappers::contains(call), Error::<T>::CallWrapperNotAllowed);
+			ensure!(
+				AllowedCalls::<T>::contains_key(Self::call_indices(call)),
+				Error::<T>::CallNotAllowed
+			);
+			Ok(())
+		}
+
+		/// Every call waiting to be dispatched by the scheduler, by id.
+		pub fn scheduled() -> Vec<(MandateId, ScheduledMandateOf<T>)> {
+			let mut scheduled: Vec<_> = ScheduledMandates::<T>::iter().collect();
+			scheduled.sort_by_key(|(id, _)| *id);
+			scheduled
+		}
+
+		fn schedule(when: T::BlockNumber, call: Box<<T as Config>::Call>) -> DispatchResult {
+			ensure!(
+				when > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::ScheduleInPast
+			);
+
+			let mandate_id = MandatesScheduled::<T>::get();
+			let call_hash = T::Hashing::hash_of(&call);
+			if T::Scheduler::schedule_named(
+				(MANDATE_ID, mandate_id).encode(),
+				DispatchTime::At(when),
+				None,
+				// same priority as the calls scheduled by the democracy pallet, they are
+				// dispatched whatever the weight left in the block
+				63,
+				frame_system::RawOrigin::Root.into(),
+				Call::<T>::execute_scheduled { mandate_id, call }.into(),
+			)
+			.is_err()
+			{
+				return Err(Error::<T>::FailedToSchedule.into());
+			}
+
+			MandatesScheduled::<T>::put(mandate_id + 1);
+			ScheduledMandates::<T>::insert(mandate_id, ScheduledMandate { call_hash, when });
+			Self::deposit_event(Event::MandateScheduled(mandate_id, when));
+			Ok(())
+		}
+
+		fn record(call_hash: T::Hash, result: DispatchResult) {
+			History::<T>::mutate(|history| {
+				history.push(MandateExecution {
+					call_hash,
+					block: frame_system::Pallet::<T>::block_number(),
+					result,
+				});
+				let excess = history.len().saturating_sub(T::MaxHistoryLength::get() as usize);
+				history.drain(..excess);
+			});
+		}
 	}
 }
@@ -0,0 +1,79 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+pub mod v1 {
+	use crate::{AllowedCalls, Config, Pallet};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Get, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_std::vec::Vec;
+
+	/// The current storage version.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	/// The mandate used to dispatch any call, it now only dispatches the calls in
+	/// `AllowedCalls`. Allows `Calls` so that the mandate can still do what governance relies
+	/// on right after the upgrade, other calls have to be allowed by the `AllowlistOrigin`.
+	pub struct SeedAllowedCalls<T, Calls>(PhantomData<(T, Calls)>);
+	impl<T: Config, Calls: Get<Vec<<T as Config>::Call>>> OnRuntimeUpgrade for SeedAllowedCalls<T, Calls> {
+		fn on_runtime_upgrade() -> Weight {
+			let weight = T::DbWeight::get().reads(1);
+			if StorageVersion::get::<Pallet<T>>() >= STORAGE_VERSION {
+				return weight;
+			}
+
+			let calls = Calls::get();
+			for call in calls.iter() {
+				AllowedCalls::<T>::insert(Pallet::<T>::call_indices(call), ());
+			}
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+			weight.saturating_add(T::DbWeight::get().writes(calls.len() as Weight + 1))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+			Self::set_temp_storage(StorageVersion::get::<Pallet<T>>() < STORAGE_VERSION, "seeding");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+			if StorageVersion::get::<Pallet<T>>() != STORAGE_VERSION {
+				return Err("Mandate storage version not bumped!");
+			}
+			// Calls allowed by an earlier upgrade may have been disallowed since.
+			if Self::get_temp_storage::<bool>("seeding") != Some(true) {
+				return Ok(());
+			}
+			if !Calls::get()
+				.iter()
+				.all(|call| AllowedCalls::<T>::contains_key(Pallet::<T>::call_indices(call)))
+			{
+				return Err("Mandate allowlist not seeded!");
+			}
+			Ok(())
+		}
+	}
+}
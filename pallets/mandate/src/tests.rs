@@ -0,0 +1,423 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+#![cfg(test)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok, bounded_vec, ord_parameter_types, parameter_types,
+	traits::{Contains, EqualPrivilegeOnly, OnInitialize, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use frame_system::{EnsureRoot, EnsureSignedBy};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Hash, IdentityLookup},
+	DispatchError::BadOrigin,
+	Perbill,
+};
+
+use crate::{self as mandate, Config, Error, ScheduledMandate};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
+		Mandate: mandate::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::simple_max(1_000_000);
+}
+impl frame_system::Config for Test {
+	type Origin = Origin;
+	type Call = Call;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type OnSetCode = ();
+	type SystemWeightInfo = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+impl pallet_scheduler::Config for Test {
+	type Event = ();
+	type Origin = Origin;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type ScheduleOrigin = EnsureRoot<u64>;
+	type PalletsOrigin = OriginCaller;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type WeightInfo = ();
+	type PreimageProvider = ();
+	type NoPreimagePostponement = ();
+}
+ord_parameter_types! {
+	pub const Committee: u64 = 1;
+	pub const AllowlistAdmin: u64 = 2;
+	pub const Hacker: u64 = 3;
+}
+parameter_types! {
+	pub const MaxHistoryLength: u32 = 3;
+	pub const MaxBatchedCalls: u32 = 2;
+	pub SeededCalls: Vec<Call> = vec![make_storage_call(0)];
+}
+pub struct CallWrappers;
+impl Contains<Call> for CallWrappers {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::Scheduler(..) | Call::Mandate(mandate::Call::apply { .. } | mandate::Call::batch_apply { .. })
+		)
+	}
+}
+impl Config for Test {
+	type Event = ();
+	type Call = Call;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type ExternalOrigin = EnsureSignedBy<Committee, u64>;
+	type AllowlistOrigin = EnsureSignedBy<AllowlistAdmin, u64>;
+	type MaxHistoryLength = MaxHistoryLength;
+	type MaxBatchedCalls = MaxBatchedCalls;
+	type CallWrappers = CallWrappers;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into()
+}
+
+fn make_storage_call(value: u64) -> Call {
+	Call::System(frame_system::Call::set_storage {
+		items: vec![(b"mandated".to_vec(), value.encode())],
+	})
+}
+
+fn allow(call: &Call) {
+	let (pallet_index, call_index) = Mandate::call_indices(call);
+	assert_ok!(Mandate::allow_call(
+		Origin::signed(AllowlistAdmin::get()),
+		pallet_index,
+		call_index
+	));
+}
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn only_allowed_calls_are_dispatched() {
+	new_test_ext().execute_with(|| {
+		let call = make_storage_call(42);
+		assert_noop!(
+			Mandate::apply(Origin::signed(Hacker::get()), Box::new(call.clone())),
+			BadOrigin
+		);
+		assert_noop!(
+			Mandate::apply(Origin::signed(Committee::get()), Box::new(call.clone())),
+			Error::<Test>::CallNotAllowed
+		);
+
+		allow(&call);
+		assert_ok!(Mandate::apply(Origin::signed(Committee::get()), Box::new(call)));
+		assert_eq!(sp_io::storage::get(b"mandated"), Some(42u64.encode()));
+	})
+}
+
+#[test]
+fn call_wrappers_are_never_dispatched() {
+	new_test_ext().execute_with(|| {
+		let call = make_storage_call(42);
+		allow(&call);
+		let wrapper = Call::Mandate(mandate::Call::apply { call: Box::new(call) });
+		allow(&wrapper);
+
+		assert_noop!(
+			Mandate::apply(Origin::signed(Committee::get()), Box::new(wrapper.clone())),
+			Error::<Test>::CallWrapperNotAllowed
+		);
+		assert_noop!(
+			Mandate::batch_apply(Origin::signed(Committee::get()), bounded_vec![wrapper.clone()]),
+			Error::<Test>::CallWrapperNotAllowed
+		);
+		assert_noop!(
+			Mandate::apply_after(Origin::signed(Committee::get()), 5, Box::new(wrapper)),
+			Error::<Test>::CallWrapperNotAllowed
+		);
+		assert_eq!(sp_io::storage::get(b"mandated"), None);
+	})
+}
+
+#[test]
+fn allowlist_is_managed_by_its_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Mandate::allow_call(Origin::signed(Committee::get()), 0, 5), BadOrigin);
+		assert_noop!(
+			Mandate::disallow_call(Origin::signed(AllowlistAdmin::get()), 0, 5),
+			Error::<Test>::NotAllowed
+		);
+
+		assert_ok!(Mandate::allow_call(Origin::signed(AllowlistAdmin::get()), 0, 5));
+		assert_eq!(Mandate::allowed_calls((0, 5)), Some(()));
+		assert_noop!(
+			Mandate::allow_call(Origin::signed(AllowlistAdmin::get()), 0, 5),
+			Error::<Test>::AlreadyAllowed
+		);
+
+		assert_ok!(Mandate::disallow_call(Origin::signed(AllowlistAdmin::get()), 0, 5));
+		assert_eq!(Mandate::allowed_calls((0, 5)), None);
+	})
+}
+
+#[test]
+fn executions_are_recorded_in_a_bounded_history() {
+	new_test_ext().execute_with(|| {
+		allow(&make_storage_call(0));
+		for value in 0..5u64 {
+			System::set_block_number(value + 1);
+			assert_ok!(Mandate::apply(
+				Origin::signed(Committee::get()),
+				Box::new(make_storage_call(value))
+			));
+		}
+
+		let history = Mandate::history();
+		assert_eq!(history.len(), MaxHistoryLength::get() as usize);
+		assert_eq!(history[0].block, 3);
+		assert_eq!(history[2].call_hash, BlakeTwo256::hash_of(&make_storage_call(4)));
+		assert_eq!(history[2].result, Ok(()));
+	})
+}
+
+#[test]
+fn batches_are_applied_atomically() {
+	new_test_ext().execute_with(|| {
+		// dispatched as root, this fails since only `AllowlistAdmin` may allow calls
+		let failing = Call::Mandate(mandate::Call::allow_call {
+			pallet_index: 0,
+			call_index: 0,
+		});
+		allow(&make_storage_call(0));
+		assert_noop!(
+			Mandate::batch_apply(
+				Origin::signed(Committee::get()),
+				bounded_vec![make_storage_call(42), failing.clone()]
+			),
+			Error::<Test>::CallNotAllowed
+		);
+
+		allow(&failing);
+		assert_ok!(Mandate::batch_apply(
+			Origin::signed(Committee::get()),
+			bounded_vec![make_storage_call(42), failing.clone()]
+		));
+		assert_eq!(sp_io::storage::get(b"mandated"), None);
+		assert_eq!(Mandate::history()[0].result, Err(BadOrigin));
+
+		assert_ok!(Mandate::batch_apply(
+			Origin::signed(Committee::get()),
+			bounded_vec![make_storage_call(42), make_storage_call(43)]
+		));
+		assert_eq!(sp_io::storage::get(b"mandated"), Some(43u64.encode()));
+		assert_eq!(
+			Mandate::history()[1].call_hash,
+			BlakeTwo256::hash_of(&vec![make_storage_call(42), make_storage_call(43)])
+		);
+	})
+}
+
+#[test]
+fn batches_are_bounded() {
+	new_test_ext().execute_with(|| {
+		let calls = vec![make_storage_call(42); MaxBatchedCalls::get() as usize + 1];
+		let mut encoded = mandate::Call::<Test>::batch_apply {
+			calls: Default::default(),
+		}
+		.encode();
+		encoded.truncate(encoded.len() - 1);
+		encoded.extend(calls.encode());
+		assert!(mandate::Call::<Test>::decode(&mut &encoded[..]).is_err());
+	})
+}
+
+#[test]
+fn allowlist_is_seeded_once_on_upgrade() {
+	new_test_ext().execute_with(|| {
+		let (pallet_index, call_index) = Mandate::call_indices(&make_storage_call(0));
+		StorageVersion::new(0).put::<Mandate>();
+		mandate::migrations::v1::SeedAllowedCalls::<Test, SeededCalls>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<Mandate>(), 1);
+		assert_eq!(Mandate::allowed_calls((pallet_index, call_index)), Some(()));
+
+		// governance disallowing a seeded call is not undone by later upgrades
+		assert_ok!(Mandate::disallow_call(
+			Origin::signed(AllowlistAdmin::get()),
+			pallet_index,
+			call_index
+		));
+		mandate::migrations::v1::SeedAllowedCalls::<Test, SeededCalls>::on_runtime_upgrade();
+		assert_eq!(Mandate::allowed_calls((pallet_index, call_index)), None);
+	})
+}
+
+#[test]
+fn calls_can_be_scheduled() {
+	new_test_ext().execute_with(|| {
+		let call = make_storage_call(42);
+		assert_noop!(
+			Mandate::apply_at(Origin::signed(Hacker::get()), 5, Box::new(call.clone())),
+			BadOrigin
+		);
+		assert_noop!(
+			Mandate::apply_at(Origin::signed(Committee::get()), 5, Box::new(call.clone())),
+			Error::<Test>::CallNotAllowed
+		);
+
+		allow(&call);
+		run_to_block(1);
+		assert_noop!(
+			Mandate::apply_at(Origin::signed(Committee::get()), 1, Box::new(call.clone())),
+			Error::<Test>::ScheduleInPast
+		);
+		assert_ok!(Mandate::apply_at(
+			Origin::signed(Committee::get()),
+			5,
+			Box::new(call.clone())
+		));
+		assert_ok!(Mandate::apply_after(
+			Origin::signed(Committee::get()),
+			6,
+			Box::new(make_storage_call(43))
+		));
+		assert_eq!(
+			Mandate::scheduled(),
+			vec![
+				(
+					0,
+					ScheduledMandate {
+						call_hash: BlakeTwo256::hash_of(&call),
+						when: 5
+					}
+				),
+				(
+					1,
+					ScheduledMandate {
+						call_hash: BlakeTwo256::hash_of(&make_storage_call(43)),
+						when: 7
+					}
+				),
+			]
+		);
+
+		run_to_block(4);
+		assert_eq!(sp_io::storage::get(b"mandated"), None);
+		run_to_block(5);
+		assert_eq!(sp_io::storage::get(b"mandated"), Some(42u64.encode()));
+		run_to_block(7);
+		assert_eq!(sp_io::storage::get(b"mandated"), Some(43u64.encode()));
+		assert_eq!(Mandate::scheduled(), vec![]);
+		assert_eq!(Mandate::history().len(), 2);
+	})
+}
+
+#[test]
+fn scheduled_calls_can_be_cancelled() {
+	new_test_ext().execute_with(|| {
+		let call = make_storage_call(42);
+		allow(&call);
+		assert_ok!(Mandate::apply_after(
+			Origin::signed(Committee::get()),
+			5,
+			Box::new(call)
+		));
+
+		assert_noop!(Mandate::cancel_scheduled(Origin::signed(Hacker::get()), 0), BadOrigin);
+		assert_noop!(
+			Mandate::cancel_scheduled(Origin::signed(Committee::get()), 1),
+			Error::<Test>::UnknownMandate
+		);
+		assert_ok!(Mandate::cancel_scheduled(Origin::signed(Committee::get()), 0));
+		assert_eq!(Mandate::scheduled(), vec![]);
+
+		run_to_block(5);
+		assert_eq!(sp_io::storage::get(b"mandated"), None);
+	})
+}
+
+#[test]
+fn scheduled_calls_disallowed_meanwhile_are_not_dispatched() {
+	new_test_ext().execute_with(|| {
+		let call = make_storage_call(42);
+		allow(&call);
+		assert_ok!(Mandate::apply_after(
+			Origin::signed(Committee::get()),
+			5,
+			Box::new(call.clone())
+		));
+
+		let (pallet_index, call_index) = Mandate::call_indices(&call);
+		assert_ok!(Mandate::disallow_call(
+			Origin::signed(AllowlistAdmin::get()),
+			pallet_index,
+			call_index
+		));
+
+		run_to_block(5);
+		assert_eq!(sp_io::storage::get(b"mandated"), None);
+		assert_eq!(Mandate::history()[0].result, Err(Error::<Test>::CallNotAllowed.into()));
+	})
+}
@@ -0,0 +1,49 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Runtime API definition for the reserve pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_reserve::{Holdings, InflowSource, OutflowKind, ReserveAccounting};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// Reports on every reserve of the runtime, each one being identified by its account.
+	pub trait ReserveApi<AccountId, Balance, AssetId, AssetBalance, ClassId, InstanceId> where
+		AccountId: Codec,
+		Balance: Codec,
+		AssetId: Codec,
+		AssetBalance: Codec,
+		ClassId: Codec,
+		InstanceId: Codec,
+	{
+		/// Accounts of the reserves of the runtime.
+		fn reserves() -> Vec<AccountId>;
+
+		/// Cumulative native inflows and outflows of `reserve`, or `None` if it is not the
+		/// account of a reserve.
+		fn accounting(reserve: AccountId) -> Option<ReserveAccounting<Balance>>;
+
+		/// Everything `reserve` holds, or `None` if it is not the account of a reserve.
+		/// Fungible assets are only listed once added with `track_asset`, the reserve does
+		/// not notice the assets it receives on its own.
+		fn holdings(reserve: AccountId) -> Option<Holdings<Balance, AssetId, AssetBalance, ClassId, InstanceId>>;
+	}
+}
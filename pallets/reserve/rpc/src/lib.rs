@@ -0,0 +1,172 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! RPC interface for the reserve pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+pub use pallet_reserve_rpc_runtime_api::ReserveApi as ReserveRuntimeApi;
+use pallet_reserve_rpc_runtime_api::{Holdings, ReserveAccounting};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[rpc]
+pub trait ReserveApi<BlockHash, AccountId, AssetId, ClassId, InstanceId> {
+	/// Accounts of the reserves of the chain.
+	#[rpc(name = "reserve_reserves")]
+	fn reserves(&self, at: Option<BlockHash>) -> Result<Vec<AccountId>>;
+
+	/// Cumulative native inflows and outflows of `reserve`, by category.
+	#[rpc(name = "reserve_accounting")]
+	fn accounting(&self, reserve: AccountId, at: Option<BlockHash>) -> Result<ReserveAccounting<NumberOrHex>>;
+
+	/// Native balance, tracked fungible assets and NFTs held by `reserve`. Fungible assets
+	/// are only tracked once added with `track_asset`.
+	#[rpc(name = "reserve_holdings")]
+	fn holdings(
+		&self,
+		reserve: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Holdings<NumberOrHex, AssetId, NumberOrHex, ClassId, InstanceId>>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The account is not the one of a reserve.
+	UnknownReserve,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::UnknownReserve => 2,
+		}
+	}
+}
+
+/// Reserve RPC methods, answered from the runtime API.
+pub struct Reserve<C, Block, Balance, AssetBalance> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(Block, Balance, AssetBalance)>,
+}
+
+impl<C, Block, Balance, AssetBalance> Reserve<C, Block, Balance, AssetBalance> {
+	/// Create new `Reserve` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn unknown_reserve() -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::UnknownReserve.into()),
+		message: "The account is not the one of a reserve.".into(),
+		data: None,
+	}
+}
+
+impl<C, Block, AccountId, Balance, AssetId, AssetBalance, ClassId, InstanceId>
+	ReserveApi<<Block as BlockT>::Hash, AccountId, AssetId, ClassId, InstanceId>
+	for Reserve<C, Block, Balance, AssetBalance>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ReserveRuntimeApi<Block, AccountId, Balance, AssetId, AssetBalance, ClassId, InstanceId>,
+	AccountId: Codec,
+	Balance: Codec + Into<NumberOrHex> + Send + Sync + 'static,
+	AssetId: Codec,
+	AssetBalance: Codec + Into<NumberOrHex> + Send + Sync + 'static,
+	ClassId: Codec,
+	InstanceId: Codec,
+{
+	fn reserves(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.reserves(&at)
+			.map_err(|e| runtime_error("Unable to query the reserves.", e))
+	}
+
+	fn accounting(
+		&self,
+		reserve: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<ReserveAccounting<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let accounting = api
+			.accounting(&at, reserve)
+			.map_err(|e| runtime_error("Unable to query the reserve accounting.", e))?
+			.ok_or_else(unknown_reserve)?;
+		Ok(ReserveAccounting {
+			inflows: accounting
+				.inflows
+				.into_iter()
+				.map(|(source, amount)| (source, amount.into()))
+				.collect(),
+			outflows: accounting
+				.outflows
+				.into_iter()
+				.map(|(kind, amount)| (kind, amount.into()))
+				.collect(),
+		})
+	}
+
+	fn holdings(
+		&self,
+		reserve: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Holdings<NumberOrHex, AssetId, NumberOrHex, ClassId, InstanceId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let holdings = api
+			.holdings(&at, reserve)
+			.map_err(|e| runtime_error("Unable to query the reserve holdings.", e))?
+			.ok_or_else(unknown_reserve)?;
+		Ok(Holdings {
+			native: holdings.native.into(),
+			assets: holdings
+				.assets
+				.into_iter()
+				.map(|(asset, amount)| (asset, amount.into()))
+				.collect(),
+			nfts: holdings.nfts,
+		})
+	}
+}
@@ -0,0 +1,109 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Cumulative inflows and outflows of a reserve, by category.
+
+use crate::{Config, NegativeImbalanceOf, Pallet};
+use codec::{Decode, Encode};
+use frame_support::traits::OnUnbalanced;
+use sp_runtime::RuntimeDebug;
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// Categories of native funds paid to a reserve.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum InflowSource {
+	/// Transaction fees, received through `FeeInflow`
+	Fees,
+	/// Dust of reaped accounts, received through `DustInflow`
+	Dust,
+	/// Tips paid with `tip`
+	Tips,
+	/// Protocol fees of the allocations
+	ProtocolFees,
+	/// Any other imbalance, or funds received by a call dispatched with `apply_as`
+	Other,
+	/// Rewards of the allocations which expired unclaimed
+	ExpiredRewards,
+}
+
+impl InflowSource {
+	pub const ALL: [InflowSource; 6] = [
+		InflowSource::Fees,
+		InflowSource::Dust,
+		InflowSource::Tips,
+		InflowSource::ProtocolFees,
+		InflowSource::Other,
+		InflowSource::ExpiredRewards,
+	];
+}
+
+/// Categories of native funds paid by a reserve.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum OutflowKind {
+	/// Direct spends
+	Spends,
+	/// Approved spend proposals
+	Proposals,
+	/// Payment streams
+	Streams,
+	/// Funds sent by calls dispatched with `apply_as`
+	ApplyAs,
+}
+
+impl OutflowKind {
+	pub const ALL: [OutflowKind; 4] = [
+		OutflowKind::Spends,
+		OutflowKind::Proposals,
+		OutflowKind::Streams,
+		OutflowKind::ApplyAs,
+	];
+}
+
+/// Cumulative native flows of a reserve, as reported by `Pallet::accounting`. Plain
+/// transfers to the reserve account can not be observed and are left out.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ReserveAccounting<Balance> {
+	/// Funds received, by source
+	pub inflows: Vec<(InflowSource, Balance)>,
+	/// Funds paid, by kind of payment
+	pub outflows: Vec<(OutflowKind, Balance)>,
+}
+
+/// Sends transaction fees to the reserve, accounted as `InflowSource::Fees`.
+pub struct FeeInflow<T, I = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> OnUnbalanced<NegativeImbalanceOf<T, I>> for FeeInflow<T, I> {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T, I>) {
+		Pallet::<T, I>::receive(amount, InflowSource::Fees);
+	}
+}
+
+/// Sends the dust of reaped accounts to the reserve, accounted as `InflowSource::Dust`.
+pub struct DustInflow<T, I = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> OnUnbalanced<NegativeImbalanceOf<T, I>> for DustInflow<T, I> {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T, I>) {
+		Pallet::<T, I>::receive(amount, InflowSource::Dust);
+	}
+}
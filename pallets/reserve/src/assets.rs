@@ -0,0 +1,156 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Assets held by a reserve besides its `Currency`.
+
+use codec::{Decode, Encode};
+use frame_support::traits::tokens::{fungibles, DepositConsequence, WithdrawConsequence};
+use sp_runtime::{DispatchError, RuntimeDebug, TokenError};
+use sp_std::prelude::*;
+#[cfg(feature = "runtime-benchmarks")]
+use {frame_support::traits::tokens::fungible, sp_runtime::DispatchResult};
+
+/// Everything a reserve account holds, as reported by `Pallet::holdings`.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Holdings<Balance, AssetId, AssetBalance, ClassId, InstanceId> {
+	/// Free balance in the native currency
+	pub native: Balance,
+	/// Balance of every fungible asset added with `track_asset`, other assets held by the
+	/// reserve are not listed
+	pub assets: Vec<(AssetId, AssetBalance)>,
+	/// Every NFT owned by the reserve account
+	pub nfts: Vec<(ClassId, InstanceId)>,
+}
+
+/// Fungibles implementation for runtimes without any fungible asset besides their
+/// `Currency`, every asset is unknown.
+pub struct NoAssets;
+
+impl<AccountId> fungibles::Inspect<AccountId> for NoAssets {
+	type AssetId = u32;
+	type Balance = u128;
+
+	fn total_issuance(_asset: Self::AssetId) -> Self::Balance {
+		0
+	}
+
+	fn minimum_balance(_asset: Self::AssetId) -> Self::Balance {
+		0
+	}
+
+	fn balance(_asset: Self::AssetId, _who: &AccountId) -> Self::Balance {
+		0
+	}
+
+	fn reducible_balance(_asset: Self::AssetId, _who: &AccountId, _keep_alive: bool) -> Self::Balance {
+		0
+	}
+
+	fn can_deposit(_asset: Self::AssetId, _who: &AccountId, _amount: Self::Balance) -> DepositConsequence {
+		DepositConsequence::UnknownAsset
+	}
+
+	fn can_withdraw(
+		_asset: Self::AssetId,
+		_who: &AccountId,
+		_amount: Self::Balance,
+	) -> WithdrawConsequence<Self::Balance> {
+		WithdrawConsequence::UnknownAsset
+	}
+}
+
+impl<AccountId> fungibles::Transfer<AccountId> for NoAssets {
+	fn transfer(
+		_asset: Self::AssetId,
+		_source: &AccountId,
+		_dest: &AccountId,
+		_amount: Self::Balance,
+		_keep_alive: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		Err(TokenError::UnknownAsset.into())
+	}
+}
+
+/// Fungibles implementation exposing a `fungible` currency under every asset id, only used
+/// to benchmark asset spends in runtimes whose `Assets` is `NoAssets`.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct NativeAssets<F>(sp_std::marker::PhantomData<F>);
+
+#[cfg(feature = "runtime-benchmarks")]
+impl<AccountId, F: fungible::Inspect<AccountId>> fungibles::Inspect<AccountId> for NativeAssets<F> {
+	type AssetId = u32;
+	type Balance = F::Balance;
+
+	fn total_issuance(_asset: Self::AssetId) -> Self::Balance {
+		F::total_issuance()
+	}
+
+	fn minimum_balance(_asset: Self::AssetId) -> Self::Balance {
+		F::minimum_balance()
+	}
+
+	fn balance(_asset: Self::AssetId, who: &AccountId) -> Self::Balance {
+		F::balance(who)
+	}
+
+	fn reducible_balance(_asset: Self::AssetId, who: &AccountId, keep_alive: bool) -> Self::Balance {
+		F::reducible_balance(who, keep_alive)
+	}
+
+	fn can_deposit(_asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> DepositConsequence {
+		F::can_deposit(who, amount)
+	}
+
+	fn can_withdraw(
+		_asset: Self::AssetId,
+		who: &AccountId,
+		amount: Self::Balance,
+	) -> WithdrawConsequence<Self::Balance> {
+		F::can_withdraw(who, amount)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl<AccountId, F: fungible::Transfer<AccountId>> fungibles::Transfer<AccountId> for NativeAssets<F> {
+	fn transfer(
+		_asset: Self::AssetId,
+		source: &AccountId,
+		dest: &AccountId,
+		amount: Self::Balance,
+		keep_alive: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		F::transfer(source, dest, amount, keep_alive)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl<AccountId, F: fungible::Mutate<AccountId>> fungibles::Mutate<AccountId> for NativeAssets<F> {
+	fn mint_into(_asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> DispatchResult {
+		F::mint_into(who, amount)
+	}
+
+	fn burn_from(
+		_asset: Self::AssetId,
+		who: &AccountId,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		F::burn_from(who, amount)
+	}
+}
@@ -24,9 +24,9 @@
 use super::*;
 
 use frame_benchmarking::{account, benchmarks_instance_pallet, impl_benchmark_test_suite};
-use frame_support::traits::{EnsureOrigin, UnfilteredDispatchable};
+use frame_support::traits::{EnsureOrigin, OnInitialize, UnfilteredDispatchable};
 use frame_system::RawOrigin;
-use sp_runtime::traits::Saturating;
+use sp_runtime::traits::{Saturating, TrailingZeroInput};
 use sp_std::prelude::*;
 
 #[cfg(test)]
@@ -34,7 +34,55 @@ use crate::Pallet as Reserve;
 
 const SEED: u32 = 0;
 
+fn fund_reserve<T: Config<I>, I: 'static>() {
+	let _ = T::Currency::make_free_balance_be(
+		&Pallet::<T, I>::account_id(),
+		T::Currency::minimum_balance().saturating_mul(1_000_000u32.into()),
+	);
+}
+
+fn add_stream<T: Config<I>, I: 'static>(index: u32, start: T::BlockNumber) -> StreamId {
+	let stream = Pallet::<T, I>::stream_count();
+	<Streams<T, I>>::insert(
+		stream,
+		PaymentStream {
+			beneficiary: account("beneficiary", index, SEED),
+			amount: T::Currency::minimum_balance().saturating_mul(100u32.into()),
+			period: 10u32.into(),
+			start,
+			end: 1_000u32.into(),
+			next_payment: start,
+			paused: false,
+			canceller: Some(account("canceller", index, SEED)),
+		},
+	);
+	<StreamCount<T, I>>::put(stream + 1);
+	<StreamAgenda<T, I>>::append(start, stream);
+	stream
+}
+
+fn add_proposal<T: Config<I>, I: 'static>(index: u32) -> ProposalIndex {
+	let proposal = Pallet::<T, I>::proposal_count();
+	<Proposals<T, I>>::insert(
+		proposal,
+		SpendProposal {
+			proposer: account("proposer", index, SEED),
+			beneficiary: account("beneficiary", index, SEED),
+			amount: T::Currency::minimum_balance().saturating_mul(100u32.into()),
+			description: vec![0; T::MaxDescriptionLength::get() as usize].try_into().unwrap(),
+		},
+	);
+	<ProposalCount<T, I>>::put(proposal + 1);
+	proposal
+}
+
 benchmarks_instance_pallet! {
+	where_clause {
+		where
+			T::Assets: fungibles::Mutate<T::AccountId>,
+			T::Uniques: nonfungibles::Create<T::AccountId> + nonfungibles::Mutate<T::AccountId>,
+	}
+
 	tip {
 		let tipper = account("caller", 0, SEED);
 		let value = 100u32.into();
@@ -42,6 +90,7 @@ benchmarks_instance_pallet! {
 	}: _(RawOrigin::Signed(tipper), value)
 
 	spend {
+		fund_reserve::<T, I>();
 		let dest = account("dest", 0, SEED);
 		let value = T::Currency::minimum_balance().saturating_mul(100u32.into());
 
@@ -52,5 +101,155 @@ benchmarks_instance_pallet! {
 		let origin = T::ExternalOrigin::successful_origin();
 	}: { call.dispatch_bypass_filter(origin)? }
 
+	propose_spend {
+		let call = Call::<T, I>::propose_spend {
+			beneficiary: account("beneficiary", 0, SEED),
+			amount: T::Currency::minimum_balance().saturating_mul(100u32.into()),
+			description: vec![0; T::MaxDescriptionLength::get() as usize].try_into().unwrap(),
+		};
+		let origin = T::ProposeOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	approve_spend {
+		for i in 1..T::MaxApprovals::get() {
+			let proposal = add_proposal::<T, I>(i);
+			<Approvals<T, I>>::append(proposal);
+		}
+		let proposal = add_proposal::<T, I>(0);
+		let call = Call::<T, I>::approve_spend { proposal };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	reject_spend {
+		for i in 0..T::MaxApprovals::get() {
+			let proposal = add_proposal::<T, I>(i);
+			<Approvals<T, I>>::append(proposal);
+		}
+		let call = Call::<T, I>::reject_spend { proposal: 0 };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	on_initialize_proposals {
+		let p in 0 .. T::MaxApprovals::get();
+
+		fund_reserve::<T, I>();
+		<SpendGuardrails<T, I>>::put(Guardrails { allowlist_only: true, ..Default::default() });
+		for i in 0..p {
+			let proposal = add_proposal::<T, I>(i);
+			<Approvals<T, I>>::append(proposal);
+			<Allowlist<T, I>>::insert(account::<T::AccountId>("beneficiary", i, SEED), ());
+		}
+	}: {
+		Pallet::<T, I>::on_initialize(Zero::zero());
+	}
+
+	create_stream {
+		let call = Call::<T, I>::create_stream {
+			beneficiary: account("beneficiary", 0, SEED),
+			amount: T::Currency::minimum_balance().saturating_mul(100u32.into()),
+			period: 10u32.into(),
+			start: 1u32.into(),
+			end: 1_000u32.into(),
+			canceller: Some(account("canceller", 0, SEED)),
+		};
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	pause_stream {
+		let stream = add_stream::<T, I>(0, One::one());
+		let call = Call::<T, I>::pause_stream { stream };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	resume_stream {
+		let stream = add_stream::<T, I>(0, One::one());
+		<Streams<T, I>>::mutate(stream, |stream| stream.as_mut().map(|stream| stream.paused = true));
+		let call = Call::<T, I>::resume_stream { stream };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	cancel_stream {
+		let stream = add_stream::<T, I>(0, One::one());
+		let call = Call::<T, I>::cancel_stream { stream };
+		let origin = T::StreamCancelOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	on_initialize_streams {
+		let s in 0 .. T::MaxStreamPayoutsPerBlock::get();
+
+		fund_reserve::<T, I>();
+		<SpendGuardrails<T, I>>::put(Guardrails { allowlist_only: true, ..Default::default() });
+		for i in 0..s {
+			add_stream::<T, I>(i, One::one());
+			<Allowlist<T, I>>::insert(account::<T::AccountId>("beneficiary", i, SEED), ());
+		}
+	}: {
+		Pallet::<T, I>::on_initialize(One::one());
+	}
+
+	spend_asset {
+		let reserve = Pallet::<T, I>::account_id();
+		let asset = AssetIdOf::<T, I>::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+		let amount = <T::Assets as fungibles::Inspect<_>>::minimum_balance(asset)
+			.saturating_mul(100u32.into())
+			.max(100u32.into());
+		<T::Assets as fungibles::Mutate<_>>::mint_into(asset, &reserve, amount.saturating_mul(10u32.into()))?;
+
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let call = Call::<T, I>::spend_asset { asset, to: dest.clone(), amount };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(<T::Assets as fungibles::Inspect<_>>::balance(asset, &dest), amount);
+	}
+
+	spend_nft {
+		fund_reserve::<T, I>();
+		let reserve = Pallet::<T, I>::account_id();
+		let class = T::NftClassId::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+		let instance = T::NftInstanceId::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+		<T::Uniques as nonfungibles::Create<_>>::create_class(&class, &reserve, &reserve)?;
+		<T::Uniques as nonfungibles::Mutate<_>>::mint_into(&class, &instance, &reserve)?;
+
+		let call = Call::<T, I>::spend_nft { class, instance, to: account("dest", 0, SEED) };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	track_asset {
+		let asset = AssetIdOf::<T, I>::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+		let call = Call::<T, I>::track_asset { asset };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	untrack_asset {
+		let asset = AssetIdOf::<T, I>::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+		<TrackedAssets<T, I>>::put(vec![asset]);
+		let call = Call::<T, I>::untrack_asset { asset };
+		let origin = T::ExternalOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	set_guardrails {
+		let guardrails = Guardrails {
+			max_per_spend: Some(T::Currency::minimum_balance().saturating_mul(100u32.into())),
+			max_per_window: Some(T::Currency::minimum_balance().saturating_mul(1_000u32.into())),
+			allowlist_only: true,
+		};
+		let call = Call::<T, I>::set_guardrails { guardrails };
+		let origin = T::GuardrailsOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	allow_beneficiary {
+		let who: T::AccountId = account("beneficiary", 0, SEED);
+		let call = Call::<T, I>::allow_beneficiary { who };
+		let origin = T::GuardrailsOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
+	disallow_beneficiary {
+		let who: T::AccountId = account("beneficiary", 0, SEED);
+		<Allowlist<T, I>>::insert(&who, ());
+		let call = Call::<T, I>::disallow_beneficiary { who };
+		let origin = T::GuardrailsOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+
 	impl_benchmark_test_suite!(Reserve, crate::tests::new_test_ext(), crate::tests::Test,);
 }
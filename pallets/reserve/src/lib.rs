@@ -25,19 +25,33 @@ mod benchmarking;
 #[cfg(test)]
 mod tests;
 
+use codec::{Decode, Encode};
 use frame_support::{
-	traits::{Currency, ExistenceRequirement, Get, Imbalance, OnUnbalanced},
-	weights::GetDispatchInfo,
-	PalletId,
+	traits::{
+		tokens::{fungibles, nonfungibles},
+		Contains, Currency, ExistenceRequirement, Get, Imbalance, OnUnbalanced,
+	},
+	transactional,
+	weights::{GetDispatchInfo, Weight},
+	BoundedVec, CloneNoBound, EqNoBound, PalletId, PartialEqNoBound, RuntimeDebugNoBound,
 };
-use sp_runtime::traits::{AccountIdConversion, Dispatchable};
-use sp_std::prelude::Box;
-use support::WithAccountId;
+use sp_runtime::{
+	traits::{AccountIdConversion, Dispatchable, One, Saturating, Zero},
+	DispatchResult, RuntimeDebug,
+};
+use sp_std::{fmt::Debug, prelude::*};
+use support::{OnProtocolFee, WithAccountId};
 
 #[cfg(feature = "std")]
 use frame_support::traits::GenesisBuild;
 
+pub mod accounting;
+pub mod assets;
 pub mod weights;
+pub use accounting::{DustInflow, FeeInflow, InflowSource, OutflowKind, ReserveAccounting};
+#[cfg(feature = "runtime-benchmarks")]
+pub use assets::NativeAssets;
+pub use assets::{Holdings, NoAssets};
 pub use weights::WeightInfo;
 
 pub use pallet::*;
@@ -45,6 +59,71 @@ pub use pallet::*;
 type BalanceOf<T, I> = <<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type NegativeImbalanceOf<T, I> =
 	<<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+pub type AssetIdOf<T, I> =
+	<<T as Config<I>>::Assets as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
+pub type AssetBalanceOf<T, I> =
+	<<T as Config<I>>::Assets as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+pub type HoldingsOf<T, I> = Holdings<
+	BalanceOf<T, I>,
+	AssetIdOf<T, I>,
+	AssetBalanceOf<T, I>,
+	<T as Config<I>>::NftClassId,
+	<T as Config<I>>::NftInstanceId,
+>;
+pub type SpendProposalOf<T, I> =
+	SpendProposal<<T as frame_system::Config>::AccountId, BalanceOf<T, I>, <T as Config<I>>::MaxDescriptionLength>;
+
+pub type PaymentStreamOf<T, I> =
+	PaymentStream<<T as frame_system::Config>::AccountId, BalanceOf<T, I>, <T as frame_system::Config>::BlockNumber>;
+
+pub type ProposalIndex = u32;
+pub type StreamId = u32;
+
+/// A request to spend funds from the reserve, paid at the next spend period once approved.
+#[derive(CloneNoBound, Encode, Decode, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, scale_info::TypeInfo)]
+#[scale_info(skip_type_params(MaxDescriptionLength))]
+pub struct SpendProposal<AccountId: Clone + Eq + Debug, Balance: Clone + Eq + Debug, MaxDescriptionLength: Get<u32>> {
+	/// Account that submitted the proposal
+	pub proposer: AccountId,
+	/// Account receiving the funds
+	pub beneficiary: AccountId,
+	/// Amount of funds to spend
+	pub amount: Balance,
+	/// What the funds are for
+	pub description: BoundedVec<u8, MaxDescriptionLength>,
+}
+
+/// Limits on the spends of the reserve, managed by `GuardrailsOrigin`.
+#[derive(Clone, Encode, Decode, Default, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct Guardrails<Balance> {
+	/// Maximum amount of a single spend, proposal or stream payment
+	pub max_per_spend: Option<Balance>,
+	/// Maximum amount spent over the last `SpendWindow` blocks
+	pub max_per_window: Option<Balance>,
+	/// Only the beneficiaries of the allowlist can receive funds
+	pub allowlist_only: bool,
+}
+
+/// Funds paid from the reserve to the same beneficiary every `period` blocks.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct PaymentStream<AccountId, Balance, BlockNumber> {
+	/// Account receiving the funds
+	pub beneficiary: AccountId,
+	/// Amount of funds paid every period
+	pub amount: Balance,
+	/// Number of blocks between two payments
+	pub period: BlockNumber,
+	/// Block of the first payment
+	pub start: BlockNumber,
+	/// No payment is made after this block
+	pub end: BlockNumber,
+	/// Block of the next payment
+	pub next_payment: BlockNumber,
+	/// Payments are skipped while the stream is paused
+	pub paused: bool,
+	/// Account that may cancel the stream, besides `StreamCancelOrigin` and `ExternalOrigin`
+	pub canceller: Option<AccountId>,
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -59,44 +138,369 @@ pub mod pallet {
 		type Currency: Currency<Self::AccountId>;
 		type Call: Parameter + Dispatchable<Origin = Self::Origin> + GetDispatchInfo;
 		type PalletId: Get<PalletId>;
+		/// Who can submit spend proposals, approving or rejecting them is left to
+		/// `ExternalOrigin`.
+		type ProposeOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+		/// Approved proposals are paid every `SpendPeriod` blocks.
+		#[pallet::constant]
+		type SpendPeriod: Get<Self::BlockNumber>;
+		/// Maximum amount of funds that can leave the reserve during a single spend period.
+		#[pallet::constant]
+		type MaxSpendPerPeriod: Get<BalanceOf<Self, I>>;
+		/// Maximum length, in bytes, of the description of a proposal.
+		#[pallet::constant]
+		type MaxDescriptionLength: Get<u32>;
+		/// Maximum number of approved proposals waiting to be paid.
+		#[pallet::constant]
+		type MaxApprovals: Get<u32>;
+		/// Who can cancel a payment stream, along with `ExternalOrigin`.
+		type StreamCancelOrigin: EnsureOrigin<Self::Origin>;
+		/// Maximum number of stream payments made in a single block, the others are delayed
+		/// to the following blocks.
+		#[pallet::constant]
+		type MaxStreamPayoutsPerBlock: Get<u32>;
+		/// Fungible assets, besides `Currency`, the reserve can hold and spend.
+		type Assets: fungibles::Transfer<Self::AccountId>;
+		/// Maximum number of assets reported by `holdings`.
+		#[pallet::constant]
+		type MaxTrackedAssets: Get<u32>;
+		/// Class of the NFTs the reserve can hold and spend.
+		type NftClassId: Parameter + Copy;
+		/// Instance of the NFTs the reserve can hold and spend.
+		type NftInstanceId: Parameter + Copy;
+		/// NFTs the reserve can hold and spend.
+		type Uniques: nonfungibles::Transfer<Self::AccountId, ClassId = Self::NftClassId, InstanceId = Self::NftInstanceId>
+			+ nonfungibles::InspectEnumerable<Self::AccountId>;
+		/// Who can change the guardrails of the reserve and its beneficiary allowlist.
+		type GuardrailsOrigin: EnsureOrigin<Self::Origin>;
+		/// Length, in blocks, of the rolling window over which `max_per_window` applies.
+		#[pallet::constant]
+		type SpendWindow: Get<Self::BlockNumber>;
+		/// Calls that `apply_as` may dispatch as the reserve account.
+		type ApplyAsFilter: Contains<<Self as Config<I>>::Call>;
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
 	#[pallet::hooks]
-	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let period = T::SpendPeriod::get();
+			let weight = if !period.is_zero() && (n % period).is_zero() {
+				Self::spend_funds()
+			} else {
+				0
+			};
+
+			weight.saturating_add(Self::pay_streams(n))
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
-		/// Spend `amount` funds from the reserve account to `to`.
+		/// Spend `amount` funds from the reserve account to `to` right away. Counts towards
+		/// the cap of the current spend period.
 		#[pallet::weight(T::WeightInfo::spend())]
 		pub fn spend(origin: OriginFor<T>, to: T::AccountId, amount: BalanceOf<T, I>) -> DispatchResultWithPostInfo {
 			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			Self::ensure_spend_allowed(&to, amount)?;
+			Self::ensure_within_limits(amount)?;
 
-			let _ = T::Currency::transfer(&Self::account_id(), &to, amount, ExistenceRequirement::KeepAlive);
+			T::Currency::transfer(&Self::account_id(), &to, amount, ExistenceRequirement::KeepAlive)?;
+			Self::note_spent(OutflowKind::Spends, amount);
 
 			Self::deposit_event(Event::SpentFunds(to, amount));
 
 			Ok(().into())
 		}
 
+		/// Submit a proposal to spend `amount` funds to `beneficiary`.
+		#[pallet::weight(T::WeightInfo::propose_spend())]
+		pub fn propose_spend(
+			origin: OriginFor<T>,
+			beneficiary: T::AccountId,
+			amount: BalanceOf<T, I>,
+			description: BoundedVec<u8, T::MaxDescriptionLength>,
+		) -> DispatchResultWithPostInfo {
+			let proposer = T::ProposeOrigin::ensure_origin(origin)?;
+
+			let index = Self::proposal_count();
+			<Proposals<T, I>>::insert(
+				index,
+				SpendProposal {
+					proposer,
+					beneficiary,
+					amount,
+					description,
+				},
+			);
+			<ProposalCount<T, I>>::put(index.saturating_add(1));
+			Self::deposit_event(Event::SpendProposed(index));
+
+			Ok(().into())
+		}
+
+		/// Approve a proposal, it will be paid at the next spend period with enough room
+		/// under the cap.
+		#[pallet::weight(T::WeightInfo::approve_spend())]
+		pub fn approve_spend(origin: OriginFor<T>, proposal: ProposalIndex) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			let details = Self::proposals(proposal).ok_or(Error::<T, I>::UnknownProposal)?;
+			Self::ensure_spend_allowed(&details.beneficiary, details.amount)?;
+
+			<Approvals<T, I>>::try_mutate(|approvals| -> DispatchResult {
+				ensure!(!approvals.contains(&proposal), Error::<T, I>::AlreadyApproved);
+				ensure!(
+					approvals.len() < T::MaxApprovals::get() as usize,
+					Error::<T, I>::TooManyApprovals
+				);
+				approvals.push(proposal);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::SpendApproved(proposal));
+
+			Ok(().into())
+		}
+
+		/// Reject a proposal that was not paid yet, whether it was approved or not.
+		#[pallet::weight(T::WeightInfo::reject_spend())]
+		pub fn reject_spend(origin: OriginFor<T>, proposal: ProposalIndex) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			ensure!(
+				<Proposals<T, I>>::contains_key(proposal),
+				Error::<T, I>::UnknownProposal
+			);
+
+			<Proposals<T, I>>::remove(proposal);
+			<Approvals<T, I>>::mutate(|approvals| approvals.retain(|index| *index != proposal));
+			Self::deposit_event(Event::SpendRejected(proposal));
+
+			Ok(().into())
+		}
+
+		/// Pay `amount` to `beneficiary` every `period` blocks, from `start` and as long as
+		/// `end` is not passed. A `start` in the past begins the stream at the next block.
+		/// `canceller`, if any, may cancel the stream on its own.
+		#[pallet::weight(T::WeightInfo::create_stream())]
+		pub fn create_stream(
+			origin: OriginFor<T>,
+			beneficiary: T::AccountId,
+			amount: BalanceOf<T, I>,
+			period: T::BlockNumber,
+			start: T::BlockNumber,
+			end: T::BlockNumber,
+			canceller: Option<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			ensure!(!period.is_zero(), Error::<T, I>::ZeroStreamPeriod);
+			Self::ensure_spend_allowed(&beneficiary, amount)?;
+
+			let start = start.max(frame_system::Pallet::<T>::block_number().saturating_add(One::one()));
+			ensure!(start <= end, Error::<T, I>::StreamEndsBeforeStart);
+
+			let id = Self::stream_count();
+			<Streams<T, I>>::insert(
+				id,
+				PaymentStream {
+					beneficiary,
+					amount,
+					period,
+					start,
+					end,
+					next_payment: start,
+					paused: false,
+					canceller,
+				},
+			);
+			<StreamCount<T, I>>::put(id.saturating_add(1));
+			<StreamAgenda<T, I>>::append(start, id);
+			Self::deposit_event(Event::StreamCreated(id));
+
+			Ok(().into())
+		}
+
+		/// Skip the payments of a stream until it is resumed.
+		#[pallet::weight(T::WeightInfo::pause_stream())]
+		pub fn pause_stream(origin: OriginFor<T>, stream: StreamId) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			Self::set_stream_paused(stream, true)?;
+			Self::deposit_event(Event::StreamPaused(stream));
+
+			Ok(().into())
+		}
+
+		/// Resume the payments of a paused stream, starting with its next scheduled payment.
+		#[pallet::weight(T::WeightInfo::resume_stream())]
+		pub fn resume_stream(origin: OriginFor<T>, stream: StreamId) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			Self::set_stream_paused(stream, false)?;
+			Self::deposit_event(Event::StreamResumed(stream));
+
+			Ok(().into())
+		}
+
+		/// Cancel a stream, none of its remaining payments are made. Besides the configured
+		/// origins, the `canceller` of the stream may cancel it.
+		#[pallet::weight(T::WeightInfo::cancel_stream())]
+		pub fn cancel_stream(origin: OriginFor<T>, stream: StreamId) -> DispatchResultWithPostInfo {
+			let canceller = Self::streams(stream).ok_or(Error::<T, I>::UnknownStream)?.canceller;
+			T::StreamCancelOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(|origin| T::ExternalOrigin::try_origin(origin).map(|_| ()))
+				.or_else(|origin| match ensure_signed(origin.clone()) {
+					Ok(who) if canceller.as_ref() == Some(&who) => Ok(()),
+					_ => Err(origin),
+				})
+				.or_else(ensure_root)?;
+
+			// the stream is simply skipped when its turn in the agenda comes
+			<Streams<T, I>>::remove(stream);
+			Self::deposit_event(Event::StreamCancelled(stream));
+
+			Ok(().into())
+		}
+
+		/// Spend `amount` of the fungible `asset` from the reserve account to `to`. Only the
+		/// native currency counts towards the cap of the spend period.
+		#[pallet::weight(T::WeightInfo::spend_asset())]
+		pub fn spend_asset(
+			origin: OriginFor<T>,
+			asset: AssetIdOf<T, I>,
+			to: T::AccountId,
+			amount: AssetBalanceOf<T, I>,
+		) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			Self::ensure_allowed_beneficiary(&to)?;
+
+			<T::Assets as fungibles::Transfer<_>>::transfer(asset, &Self::account_id(), &to, amount, true)?;
+			Self::deposit_event(Event::AssetSpent(asset, to, amount));
+
+			Ok(().into())
+		}
+
+		/// Transfer the NFT `instance` of `class` from the reserve account to `to`.
+		#[pallet::weight(T::WeightInfo::spend_nft())]
+		pub fn spend_nft(
+			origin: OriginFor<T>,
+			class: T::NftClassId,
+			instance: T::NftInstanceId,
+			to: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			Self::ensure_allowed_beneficiary(&to)?;
+			ensure!(
+				<T::Uniques as nonfungibles::Inspect<_>>::owner(&class, &instance) == Some(Self::account_id()),
+				Error::<T, I>::NotNftOwner
+			);
+
+			<T::Uniques as nonfungibles::Transfer<_>>::transfer(&class, &instance, &to)?;
+			Self::deposit_event(Event::NftSpent(class, instance, to));
+
+			Ok(().into())
+		}
+
+		/// Report the balance of `asset` in `holdings`. Assets received through XCM or
+		/// transfers are not noticed by the reserve on their own.
+		#[pallet::weight(T::WeightInfo::track_asset())]
+		pub fn track_asset(origin: OriginFor<T>, asset: AssetIdOf<T, I>) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+
+			<TrackedAssets<T, I>>::try_mutate(|assets| -> DispatchResult {
+				ensure!(!assets.contains(&asset), Error::<T, I>::AssetAlreadyTracked);
+				ensure!(
+					assets.len() < T::MaxTrackedAssets::get() as usize,
+					Error::<T, I>::TooManyTrackedAssets
+				);
+				assets.push(asset);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::AssetTracked(asset));
+
+			Ok(().into())
+		}
+
+		/// Stop reporting `asset` in `holdings`.
+		#[pallet::weight(T::WeightInfo::untrack_asset())]
+		pub fn untrack_asset(origin: OriginFor<T>, asset: AssetIdOf<T, I>) -> DispatchResultWithPostInfo {
+			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+
+			<TrackedAssets<T, I>>::try_mutate(|assets| -> DispatchResult {
+				let position = assets
+					.iter()
+					.position(|tracked| *tracked == asset)
+					.ok_or(Error::<T, I>::AssetNotTracked)?;
+				assets.remove(position);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::AssetUntracked(asset));
+
+			Ok(().into())
+		}
+
+		/// Replace the guardrails limiting the spends of the reserve.
+		#[pallet::weight(T::WeightInfo::set_guardrails())]
+		pub fn set_guardrails(
+			origin: OriginFor<T>,
+			guardrails: Guardrails<BalanceOf<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			T::GuardrailsOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			<SpendGuardrails<T, I>>::put(guardrails.clone());
+			Self::deposit_event(Event::GuardrailsSet(guardrails));
+
+			Ok(().into())
+		}
+
+		/// Add `who` to the beneficiary allowlist.
+		#[pallet::weight(T::WeightInfo::allow_beneficiary())]
+		pub fn allow_beneficiary(origin: OriginFor<T>, who: T::AccountId) -> DispatchResultWithPostInfo {
+			T::GuardrailsOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(!<Allowlist<T, I>>::contains_key(&who), Error::<T, I>::AlreadyAllowed);
+
+			<Allowlist<T, I>>::insert(&who, ());
+			Self::deposit_event(Event::BeneficiaryAllowed(who));
+
+			Ok(().into())
+		}
+
+		/// Remove `who` from the beneficiary allowlist.
+		#[pallet::weight(T::WeightInfo::disallow_beneficiary())]
+		pub fn disallow_beneficiary(origin: OriginFor<T>, who: T::AccountId) -> DispatchResultWithPostInfo {
+			T::GuardrailsOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+			ensure!(<Allowlist<T, I>>::contains_key(&who), Error::<T, I>::NotAllowed);
+
+			<Allowlist<T, I>>::remove(&who);
+			Self::deposit_event(Event::BeneficiaryDisallowed(who));
+
+			Ok(().into())
+		}
+
 		/// Deposit `amount` tokens in the treasure account
 		#[pallet::weight(T::WeightInfo::tip())]
 		pub fn tip(origin: OriginFor<T>, amount: BalanceOf<T, I>) -> DispatchResultWithPostInfo {
 			let tipper = ensure_signed(origin)?;
 
-			let _ = T::Currency::transfer(&tipper, &Self::account_id(), amount, ExistenceRequirement::AllowDeath);
+			T::Currency::transfer(&tipper, &Self::account_id(), amount, ExistenceRequirement::AllowDeath)?;
+			Self::note_inflow(InflowSource::Tips, amount);
 
 			Self::deposit_event(Event::TipReceived(tipper, amount));
 
 			Ok(().into())
 		}
 
-		/// Dispatch a call as coming from the reserve account
+		/// Dispatch a call as coming from the reserve account. Native funds it makes leave the
+		/// reserve count towards the cap of the spend period and the guardrails.
 		#[pallet::weight({
             let dispatch_info = call.get_dispatch_info();
             (
@@ -104,10 +508,22 @@ pub mod pallet {
                 dispatch_info.class,
             )
         })]
+		#[transactional]
 		pub fn apply_as(origin: OriginFor<T>, call: Box<<T as Config<I>>::Call>) -> DispatchResultWithPostInfo {
 			T::ExternalOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
+			ensure!(T::ApplyAsFilter::contains(&call), Error::<T, I>::CallNotAllowed);
 
+			let before = T::Currency::free_balance(&Self::account_id());
 			let res = call.dispatch(frame_system::RawOrigin::Signed(Self::account_id()).into());
+			let after = T::Currency::free_balance(&Self::account_id());
+			if after < before {
+				let spent = before - after;
+				Self::ensure_below_max_per_spend(spent)?;
+				Self::ensure_within_limits(spent)?;
+				Self::note_spent(OutflowKind::ApplyAs, spent);
+			} else if after > before {
+				Self::note_inflow(InflowSource::Other, after - before);
+			}
 
 			Self::deposit_event(Event::ReserveOp(res.map(|_| ()).map_err(|e| e.error)));
 
@@ -126,8 +542,160 @@ pub mod pallet {
 		TipReceived(T::AccountId, BalanceOf<T, I>),
 		/// We executed a call coming from the company reserve account
 		ReserveOp(DispatchResult),
+		/// A spend proposal was submitted \[proposal\]
+		SpendProposed(ProposalIndex),
+		/// A spend proposal was approved \[proposal\]
+		SpendApproved(ProposalIndex),
+		/// A spend proposal was rejected and removed \[proposal\]
+		SpendRejected(ProposalIndex),
+		/// An approved proposal was paid \[proposal, beneficiary, amount\]
+		ProposalPaid(ProposalIndex, T::AccountId, BalanceOf<T, I>),
+		/// Paying an approved proposal failed, it stays approved and is retried at the next
+		/// spend period \[proposal, error\]
+		ProposalPaymentFailed(ProposalIndex, DispatchError),
+		/// A payment stream was created \[stream\]
+		StreamCreated(StreamId),
+		/// A payment of a stream was made \[stream, beneficiary, amount\]
+		StreamPaid(StreamId, T::AccountId, BalanceOf<T, I>),
+		/// A payment of a stream failed and was skipped \[stream, error\]
+		StreamPaymentFailed(StreamId, DispatchError),
+		/// A payment stream was paused \[stream\]
+		StreamPaused(StreamId),
+		/// A payment stream was resumed \[stream\]
+		StreamResumed(StreamId),
+		/// A payment stream was cancelled \[stream\]
+		StreamCancelled(StreamId),
+		/// A payment stream reached its end \[stream\]
+		StreamEnded(StreamId),
+		/// Some fungible assets were spent from the reserve \[asset, to, amount\]
+		AssetSpent(AssetIdOf<T, I>, T::AccountId, AssetBalanceOf<T, I>),
+		/// An NFT was transferred from the reserve \[class, instance, to\]
+		NftSpent(T::NftClassId, T::NftInstanceId, T::AccountId),
+		/// An asset is now reported in the holdings of the reserve \[asset\]
+		AssetTracked(AssetIdOf<T, I>),
+		/// An asset is no longer reported in the holdings of the reserve \[asset\]
+		AssetUntracked(AssetIdOf<T, I>),
+		/// The guardrails of the reserve were replaced \[guardrails\]
+		GuardrailsSet(Guardrails<BalanceOf<T, I>>),
+		/// An account was added to the beneficiary allowlist \[who\]
+		BeneficiaryAllowed(T::AccountId),
+		/// An account was removed from the beneficiary allowlist \[who\]
+		BeneficiaryDisallowed(T::AccountId),
 	}
 
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// Spending this much would exceed the cap of the current spend period
+		PeriodCapExceeded,
+		/// There is no proposal with this index
+		UnknownProposal,
+		/// The proposal was already approved
+		AlreadyApproved,
+		/// There are already `MaxApprovals` proposals waiting to be paid
+		TooManyApprovals,
+		/// There is no stream with this id
+		UnknownStream,
+		/// A stream can not pay every zero blocks
+		ZeroStreamPeriod,
+		/// The end of the stream is before its start
+		StreamEndsBeforeStart,
+		/// The stream is already paused
+		StreamAlreadyPaused,
+		/// The stream is not paused
+		StreamNotPaused,
+		/// The reserve account does not own this NFT
+		NotNftOwner,
+		/// The asset is already tracked
+		AssetAlreadyTracked,
+		/// The asset is not tracked
+		AssetNotTracked,
+		/// There are already `MaxTrackedAssets` tracked assets
+		TooManyTrackedAssets,
+		/// The amount is above the `max_per_spend` guardrail
+		SpendAboveLimit,
+		/// Spending this much would exceed the `max_per_window` guardrail
+		WindowLimitExceeded,
+		/// The beneficiary is not on the allowlist
+		BeneficiaryNotAllowed,
+		/// `apply_as` can not dispatch this call
+		CallNotAllowed,
+		/// The account is already on the allowlist
+		AlreadyAllowed,
+		/// The account is not on the allowlist
+		NotAllowed,
+	}
+
+	/// Number of spend proposals submitted so far
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_count)]
+	pub type ProposalCount<T: Config<I>, I: 'static = ()> = StorageValue<_, ProposalIndex, ValueQuery>;
+
+	/// Spend proposals that were neither paid nor rejected yet
+	#[pallet::storage]
+	#[pallet::getter(fn proposals)]
+	pub type Proposals<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, ProposalIndex, SpendProposalOf<T, I>, OptionQuery>;
+
+	/// Approved proposals, paid in this order at the next spend periods
+	#[pallet::storage]
+	#[pallet::getter(fn approvals)]
+	pub type Approvals<T: Config<I>, I: 'static = ()> = StorageValue<_, Vec<ProposalIndex>, ValueQuery>;
+
+	/// Funds that left the reserve during the current spend period
+	#[pallet::storage]
+	#[pallet::getter(fn spent_this_period)]
+	pub type SpentThisPeriod<T: Config<I>, I: 'static = ()> = StorageValue<_, BalanceOf<T, I>, ValueQuery>;
+
+	/// Number of payment streams created so far
+	#[pallet::storage]
+	#[pallet::getter(fn stream_count)]
+	pub type StreamCount<T: Config<I>, I: 'static = ()> = StorageValue<_, StreamId, ValueQuery>;
+
+	/// Payment streams that were neither cancelled nor ended yet
+	#[pallet::storage]
+	#[pallet::getter(fn streams)]
+	pub type Streams<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, StreamId, PaymentStreamOf<T, I>, OptionQuery>;
+
+	/// Streams due for a payment at a given block
+	#[pallet::storage]
+	#[pallet::getter(fn stream_agenda)]
+	pub type StreamAgenda<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<StreamId>, ValueQuery>;
+
+	/// Fungible assets reported in the holdings of the reserve
+	#[pallet::storage]
+	#[pallet::getter(fn tracked_assets)]
+	pub type TrackedAssets<T: Config<I>, I: 'static = ()> = StorageValue<_, Vec<AssetIdOf<T, I>>, ValueQuery>;
+
+	/// Limits on the spends of the reserve
+	#[pallet::storage]
+	#[pallet::getter(fn guardrails)]
+	pub type SpendGuardrails<T: Config<I>, I: 'static = ()> = StorageValue<_, Guardrails<BalanceOf<T, I>>, ValueQuery>;
+
+	/// Beneficiaries allowed to receive funds when `allowlist_only` is set
+	#[pallet::storage]
+	#[pallet::getter(fn allowlist)]
+	pub type Allowlist<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// Native spends of the last `SpendWindow` blocks, by block
+	#[pallet::storage]
+	#[pallet::getter(fn recent_spends)]
+	pub type RecentSpends<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, Vec<(T::BlockNumber, BalanceOf<T, I>)>, ValueQuery>;
+
+	/// Cumulative native funds received by the reserve, by source
+	#[pallet::storage]
+	#[pallet::getter(fn inflows)]
+	pub type Inflows<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, InflowSource, BalanceOf<T, I>, ValueQuery>;
+
+	/// Cumulative native funds paid by the reserve, by kind of payment
+	#[pallet::storage]
+	#[pallet::getter(fn outflows)]
+	pub type Outflows<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, OutflowKind, BalanceOf<T, I>, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		pub phantom: sp_std::marker::PhantomData<(T, I)>,
@@ -171,6 +739,239 @@ impl<T: Config<I>, I: 'static> GenesisConfig<T, I> {
 	}
 }
 
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Native balance, balance of every tracked asset and every NFT of the reserve account.
+	/// Assets are tracked manually, through `track_asset`, as `Assets` can not enumerate the
+	/// assets an account holds.
+	pub fn holdings() -> HoldingsOf<T, I> {
+		let account = Self::account_id();
+		Holdings {
+			native: T::Currency::free_balance(&account),
+			assets: Self::tracked_assets()
+				.into_iter()
+				.map(|asset| (asset, <T::Assets as fungibles::Inspect<_>>::balance(asset, &account)))
+				.collect(),
+			nfts: <T::Uniques as nonfungibles::InspectEnumerable<_>>::owned(&account).collect(),
+		}
+	}
+
+	/// Start a new spend period and pay the approved proposals that fit under its cap, in
+	/// order of approval.
+	fn spend_funds() -> Weight {
+		let approvals = Self::approvals();
+		let approvals_len = approvals.len() as u32;
+
+		<SpentThisPeriod<T, I>>::kill();
+		let mut unpaid = Vec::new();
+		for index in approvals {
+			let proposal = match Self::proposals(index) {
+				Some(proposal) => proposal,
+				None => continue,
+			};
+
+			if Self::ensure_within_limits(proposal.amount).is_err() {
+				unpaid.push(index);
+				continue;
+			}
+
+			// the guardrails may have been tightened since the proposal was approved
+			let paid = Self::ensure_spend_allowed(&proposal.beneficiary, proposal.amount).and_then(|_| {
+				T::Currency::transfer(
+					&Self::account_id(),
+					&proposal.beneficiary,
+					proposal.amount,
+					ExistenceRequirement::KeepAlive,
+				)
+			});
+
+			match paid {
+				Ok(()) => {
+					Self::note_spent(OutflowKind::Proposals, proposal.amount);
+					<Proposals<T, I>>::remove(index);
+					Self::deposit_event(Event::ProposalPaid(index, proposal.beneficiary, proposal.amount));
+				}
+				Err(error) => {
+					unpaid.push(index);
+					Self::deposit_event(Event::ProposalPaymentFailed(index, error));
+				}
+			}
+		}
+
+		<Approvals<T, I>>::put(unpaid);
+
+		T::WeightInfo::on_initialize_proposals(approvals_len)
+	}
+
+	/// Make the stream payments due at block `n`, up to `MaxStreamPayoutsPerBlock` of them.
+	fn pay_streams(n: T::BlockNumber) -> Weight {
+		let mut due = <StreamAgenda<T, I>>::take(n);
+		let max_payouts = T::MaxStreamPayoutsPerBlock::get() as usize;
+		if due.len() > max_payouts {
+			let delayed = due.split_off(max_payouts);
+			<StreamAgenda<T, I>>::mutate(n.saturating_add(One::one()), |agenda| agenda.extend(delayed));
+		}
+
+		let due_len = due.len() as u32;
+		for id in due {
+			Self::pay_stream(id, n);
+		}
+
+		T::WeightInfo::on_initialize_streams(due_len)
+	}
+
+	fn pay_stream(id: StreamId, n: T::BlockNumber) {
+		let mut stream = match Self::streams(id) {
+			Some(stream) => stream,
+			None => return,
+		};
+
+		if !stream.paused {
+			// the guardrails may have been tightened since the stream was created
+			let paid = Self::ensure_spend_allowed(&stream.beneficiary, stream.amount)
+				.and_then(|_| Self::ensure_within_limits(stream.amount))
+				.and_then(|_| {
+					T::Currency::transfer(
+						&Self::account_id(),
+						&stream.beneficiary,
+						stream.amount,
+						ExistenceRequirement::KeepAlive,
+					)
+				});
+
+			match paid {
+				Ok(()) => {
+					Self::note_spent(OutflowKind::Streams, stream.amount);
+					Self::deposit_event(Event::StreamPaid(id, stream.beneficiary.clone(), stream.amount));
+				}
+				Err(error) => Self::deposit_event(Event::StreamPaymentFailed(id, error)),
+			}
+		}
+
+		// keep the cadence of the stream, unless its payment had to be delayed for too long
+		let next_payment = stream
+			.next_payment
+			.saturating_add(stream.period)
+			.max(n.saturating_add(One::one()));
+		if next_payment > stream.end {
+			<Streams<T, I>>::remove(id);
+			Self::deposit_event(Event::StreamEnded(id));
+		} else {
+			stream.next_payment = next_payment;
+			<Streams<T, I>>::insert(id, stream);
+			<StreamAgenda<T, I>>::append(next_payment, id);
+		}
+	}
+
+	fn ensure_allowed_beneficiary(who: &T::AccountId) -> DispatchResult {
+		ensure!(
+			!Self::guardrails().allowlist_only || <Allowlist<T, I>>::contains_key(who),
+			Error::<T, I>::BeneficiaryNotAllowed
+		);
+		Ok(())
+	}
+
+	/// Check `amount` can be sent to `who` in a single spend, regardless of what was spent
+	/// recently.
+	fn ensure_spend_allowed(who: &T::AccountId, amount: BalanceOf<T, I>) -> DispatchResult {
+		Self::ensure_allowed_beneficiary(who)?;
+		Self::ensure_below_max_per_spend(amount)
+	}
+
+	fn ensure_below_max_per_spend(amount: BalanceOf<T, I>) -> DispatchResult {
+		if let Some(max_per_spend) = Self::guardrails().max_per_spend {
+			ensure!(amount <= max_per_spend, Error::<T, I>::SpendAboveLimit);
+		}
+		Ok(())
+	}
+
+	/// Check `amount` can leave the reserve now, given the cap of the spend period and the
+	/// rolling window.
+	fn ensure_within_limits(amount: BalanceOf<T, I>) -> DispatchResult {
+		ensure!(
+			Self::spent_this_period().saturating_add(amount) <= T::MaxSpendPerPeriod::get(),
+			Error::<T, I>::PeriodCapExceeded
+		);
+		if let Some(max_per_window) = Self::guardrails().max_per_window {
+			ensure!(
+				Self::spent_in_window().saturating_add(amount) <= max_per_window,
+				Error::<T, I>::WindowLimitExceeded
+			);
+		}
+		Ok(())
+	}
+
+	fn note_spent(kind: OutflowKind, amount: BalanceOf<T, I>) {
+		<SpentThisPeriod<T, I>>::mutate(|spent| *spent = spent.saturating_add(amount));
+		Self::note_outflow(kind, amount);
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let window = T::SpendWindow::get();
+		<RecentSpends<T, I>>::mutate(|spends| {
+			spends.retain(|(at, _)| at.saturating_add(window) > now);
+			spends.push((now, amount));
+		});
+	}
+
+	/// Native funds that left the reserve over the last `SpendWindow` blocks.
+	pub fn spent_in_window() -> BalanceOf<T, I> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let window = T::SpendWindow::get();
+		Self::recent_spends()
+			.into_iter()
+			.filter(|(at, _)| at.saturating_add(window) > now)
+			.fold(Zero::zero(), |total: BalanceOf<T, I>, (_, amount)| {
+				total.saturating_add(amount)
+			})
+	}
+
+	fn note_inflow(source: InflowSource, amount: BalanceOf<T, I>) {
+		<Inflows<T, I>>::mutate(source, |total| *total = total.saturating_add(amount));
+	}
+
+	fn note_outflow(kind: OutflowKind, amount: BalanceOf<T, I>) {
+		<Outflows<T, I>>::mutate(kind, |total| *total = total.saturating_add(amount));
+	}
+
+	/// Resolve `amount` into the reserve account, accounted as coming from `source`.
+	pub(crate) fn receive(amount: NegativeImbalanceOf<T, I>, source: InflowSource) {
+		let numeric_amount = amount.peek();
+
+		// Must resolve into existing but better to be safe.
+		T::Currency::resolve_creating(&Self::account_id(), amount);
+		Self::note_inflow(source, numeric_amount);
+
+		Self::deposit_event(Event::Deposit(numeric_amount));
+	}
+
+	/// Cumulative native inflows and outflows of the reserve, for every category.
+	pub fn accounting() -> ReserveAccounting<BalanceOf<T, I>> {
+		ReserveAccounting {
+			inflows: InflowSource::ALL
+				.iter()
+				.map(|source| (*source, Self::inflows(source)))
+				.collect(),
+			outflows: OutflowKind::ALL
+				.iter()
+				.map(|kind| (*kind, Self::outflows(kind)))
+				.collect(),
+		}
+	}
+
+	fn set_stream_paused(id: StreamId, paused: bool) -> DispatchResult {
+		<Streams<T, I>>::try_mutate(id, |maybe_stream| -> DispatchResult {
+			let stream = maybe_stream.as_mut().ok_or(Error::<T, I>::UnknownStream)?;
+			match (stream.paused, paused) {
+				(true, true) => Err(Error::<T, I>::StreamAlreadyPaused.into()),
+				(false, false) => Err(Error::<T, I>::StreamNotPaused.into()),
+				_ => {
+					stream.paused = paused;
+					Ok(())
+				}
+			}
+		})
+	}
+}
+
 impl<T: Config<I>, I: 'static> WithAccountId<T::AccountId> for Pallet<T, I> {
 	fn account_id() -> T::AccountId {
 		T::PalletId::get().into_account()
@@ -179,11 +980,16 @@ impl<T: Config<I>, I: 'static> WithAccountId<T::AccountId> for Pallet<T, I> {
 
 impl<T: Config<I>, I: 'static> OnUnbalanced<NegativeImbalanceOf<T, I>> for Pallet<T, I> {
 	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T, I>) {
-		let numeric_amount = amount.peek();
+		Self::receive(amount, InflowSource::Other);
+	}
+}
 
-		// Must resolve into existing but better to be safe.
-		T::Currency::resolve_creating(&Self::account_id(), amount);
+impl<T: Config<I>, I: 'static> OnProtocolFee<BalanceOf<T, I>> for Pallet<T, I> {
+	fn on_protocol_fee(amount: BalanceOf<T, I>) {
+		Self::note_inflow(InflowSource::ProtocolFees, amount);
+	}
 
-		Self::deposit_event(Event::Deposit(numeric_amount));
+	fn on_expired_rewards(amount: BalanceOf<T, I>) {
+		Self::note_inflow(InflowSource::ExpiredRewards, amount);
 	}
 }
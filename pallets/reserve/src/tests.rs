@@ -20,15 +20,26 @@
 
 use super::*;
 use crate::{self as pallet_reserve};
-use frame_support::{assert_noop, assert_ok, ord_parameter_types, parameter_types, traits::Currency};
-use frame_system::{EnsureSignedBy, RawOrigin};
+use frame_support::{
+	assert_noop, assert_ok, ord_parameter_types, parameter_types,
+	traits::{
+		tokens::{
+			fungibles::{self, Inspect as _},
+			DepositConsequence, WithdrawConsequence,
+		},
+		AsEnsureOriginWithArg, Contains, Currency, OnInitialize,
+	},
+};
+use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy, RawOrigin};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
 	DispatchError::BadOrigin,
 };
+use sp_runtime::{DispatchError, TokenError};
 use sp_std::prelude::Box;
+use std::{cell::RefCell, collections::HashMap};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -41,6 +52,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Config<T>, Storage, Event<T>},
+		Uniques: pallet_uniques::{Pallet, Call, Storage, Event<T>},
 		TestModule: pallet_reserve::{Pallet, Call, Storage, Event<T>},
 	}
 );
@@ -89,18 +101,152 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const UniquesDeposit: u64 = 0;
+	pub const UniquesLimit: u32 = 32;
+}
+impl pallet_uniques::Config for Test {
+	type Event = ();
+	type ClassId = u32;
+	type InstanceId = u32;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<u64>;
+	type ClassDeposit = UniquesDeposit;
+	type InstanceDeposit = UniquesDeposit;
+	type MetadataDepositBase = UniquesDeposit;
+	type AttributeDepositBase = UniquesDeposit;
+	type DepositPerByte = UniquesDeposit;
+	type StringLimit = UniquesLimit;
+	type KeyLimit = UniquesLimit;
+	type ValueLimit = UniquesLimit;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type Helper = ();
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<u64>>;
+}
+
+thread_local! {
+	static ASSETS: RefCell<HashMap<(u32, u64), u64>> = RefCell::new(HashMap::new());
+}
+
+/// Fungible assets without any existential deposit, kept in memory.
+pub struct TestAssets;
+impl TestAssets {
+	pub fn set_balance(asset: u32, who: u64, amount: u64) {
+		ASSETS.with(|assets| assets.borrow_mut().insert((asset, who), amount));
+	}
+}
+impl fungibles::Inspect<u64> for TestAssets {
+	type AssetId = u32;
+	type Balance = u64;
+
+	fn total_issuance(asset: u32) -> u64 {
+		ASSETS.with(|assets| {
+			assets
+				.borrow()
+				.iter()
+				.filter(|((id, _), _)| *id == asset)
+				.map(|(_, amount)| amount)
+				.sum()
+		})
+	}
+	fn minimum_balance(_asset: u32) -> u64 {
+		0
+	}
+	fn balance(asset: u32, who: &u64) -> u64 {
+		ASSETS.with(|assets| assets.borrow().get(&(asset, *who)).copied().unwrap_or(0))
+	}
+	fn reducible_balance(asset: u32, who: &u64, _keep_alive: bool) -> u64 {
+		Self::balance(asset, who)
+	}
+	fn can_deposit(_asset: u32, _who: &u64, _amount: u64) -> DepositConsequence {
+		DepositConsequence::Success
+	}
+	fn can_withdraw(asset: u32, who: &u64, amount: u64) -> WithdrawConsequence<u64> {
+		if Self::balance(asset, who) < amount {
+			WithdrawConsequence::NoFunds
+		} else {
+			WithdrawConsequence::Success
+		}
+	}
+}
+impl fungibles::Transfer<u64> for TestAssets {
+	fn transfer(asset: u32, source: &u64, dest: &u64, amount: u64, _keep_alive: bool) -> Result<u64, DispatchError> {
+		let balance = Self::balance(asset, source);
+		if balance < amount {
+			return Err(TokenError::NoFunds.into());
+		}
+		Self::set_balance(asset, *source, balance - amount);
+		Self::set_balance(asset, *dest, Self::balance(asset, dest) + amount);
+		Ok(amount)
+	}
+}
+impl fungibles::Mutate<u64> for TestAssets {
+	fn mint_into(asset: u32, who: &u64, amount: u64) -> DispatchResult {
+		Self::set_balance(asset, *who, Self::balance(asset, who) + amount);
+		Ok(())
+	}
+	fn burn_from(asset: u32, who: &u64, amount: u64) -> Result<u64, DispatchError> {
+		let balance = Self::balance(asset, who);
+		if balance < amount {
+			return Err(TokenError::NoFunds.into());
+		}
+		Self::set_balance(asset, *who, balance - amount);
+		Ok(amount)
+	}
+}
+
 ord_parameter_types! {
 	pub const Admin: u64 = 1;
+	pub const Proposer: u64 = 2;
+	pub const StreamCanceller: u64 = 10;
+	pub const GuardrailsAdmin: u64 = 11;
 }
 parameter_types! {
 	pub const ReserveModuleId: PalletId = PalletId(*b"py/resrv");
+	pub const SpendPeriod: u64 = 5;
+	pub const MaxSpendPerPeriod: u64 = 1_000;
+	pub const MaxDescriptionLength: u32 = 16;
+	pub const MaxApprovals: u32 = 3;
+	pub const MaxStreamPayoutsPerBlock: u32 = 2;
+	pub const MaxTrackedAssets: u32 = 2;
+	pub const SpendWindow: u64 = 10;
 }
+
+/// Only lets the reserve dispatch remarks, and transfers keeping it alive to check how they
+/// are accounted.
+pub struct RemarksOnly;
+impl Contains<Call> for RemarksOnly {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::System(frame_system::Call::remark { .. })
+				| Call::Balances(pallet_balances::Call::transfer_keep_alive { .. })
+		)
+	}
+}
+
 impl Config for Test {
 	type Event = ();
 	type Currency = pallet_balances::Pallet<Self>;
 	type ExternalOrigin = EnsureSignedBy<Admin, u64>;
 	type Call = Call;
 	type PalletId = ReserveModuleId;
+	type ProposeOrigin = EnsureSignedBy<Proposer, u64>;
+	type SpendPeriod = SpendPeriod;
+	type MaxSpendPerPeriod = MaxSpendPerPeriod;
+	type MaxDescriptionLength = MaxDescriptionLength;
+	type MaxApprovals = MaxApprovals;
+	type StreamCancelOrigin = EnsureSignedBy<StreamCanceller, u64>;
+	type MaxStreamPayoutsPerBlock = MaxStreamPayoutsPerBlock;
+	type Assets = TestAssets;
+	type MaxTrackedAssets = MaxTrackedAssets;
+	type NftClassId = u32;
+	type NftInstanceId = u32;
+	type Uniques = Uniques;
+	type GuardrailsOrigin = EnsureSignedBy<GuardrailsAdmin, u64>;
+	type SpendWindow = SpendWindow;
+	type ApplyAsFilter = RemarksOnly;
 	type WeightInfo = ();
 }
 type TestCurrency = <Test as Config>::Currency;
@@ -163,6 +309,20 @@ fn apply_as_works() {
 	})
 }
 
+#[test]
+fn apply_as_filters_calls() {
+	new_test_ext().execute_with(|| {
+		let call = Box::new(Call::Balances(pallet_balances::Call::<Test>::transfer {
+			dest: 3,
+			value: 1,
+		}));
+		assert_noop!(
+			TestModule::apply_as(Origin::signed(Admin::get()), call),
+			Error::<Test>::CallNotAllowed
+		);
+	})
+}
+
 #[test]
 fn try_root_if_not_admin() {
 	new_test_ext().execute_with(|| {
@@ -172,3 +332,822 @@ fn try_root_if_not_admin() {
 		assert_ok!(TestModule::apply_as(RawOrigin::Root.into(), make_call(1)));
 	})
 }
+
+#[test]
+fn spend_fails_loudly() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 50);
+
+		assert_noop!(
+			TestModule::spend(Origin::signed(Admin::get()), 3, 100),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_eq!(TestModule::spent_this_period(), 0);
+	})
+}
+
+#[test]
+fn spend_is_capped_per_period() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 2_000);
+
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 600));
+		assert_noop!(
+			TestModule::spend(Origin::signed(Admin::get()), 3, 500),
+			Error::<Test>::PeriodCapExceeded
+		);
+
+		TestModule::on_initialize(4);
+		assert_eq!(TestModule::spent_this_period(), 600);
+		TestModule::on_initialize(5);
+		assert_eq!(TestModule::spent_this_period(), 0);
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 500));
+		assert_eq!(Balances::free_balance(3), 1_100);
+	})
+}
+
+#[test]
+fn propose_spend_checks_origin_and_description() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::propose_spend(Origin::signed(Admin::get()), 3, 100, Default::default()),
+			BadOrigin
+		);
+		// an over long description does not even decode
+		let call = pallet_reserve::Call::<Test>::propose_spend {
+			beneficiary: 3,
+			amount: 100,
+			description: Default::default(),
+		}
+		.encode();
+		let mut over_long = call[..call.len() - 1].to_vec();
+		over_long.extend(vec![0u8; 17].encode());
+		assert!(pallet_reserve::Call::<Test>::decode(&mut &over_long[..]).is_err());
+
+		assert_ok!(TestModule::propose_spend(
+			Origin::signed(Proposer::get()),
+			3,
+			100,
+			b"audit".to_vec().try_into().unwrap()
+		));
+		assert_eq!(TestModule::proposal_count(), 1);
+		assert_eq!(
+			TestModule::proposals(0),
+			Some(SpendProposal {
+				proposer: Proposer::get(),
+				beneficiary: 3,
+				amount: 100,
+				description: b"audit".to_vec().try_into().unwrap(),
+			})
+		);
+	})
+}
+
+#[test]
+fn approved_proposals_are_paid_on_spend_period() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 2_000);
+		for beneficiary in 3..6 {
+			assert_ok!(TestModule::propose_spend(
+				Origin::signed(Proposer::get()),
+				beneficiary,
+				100,
+				Default::default()
+			));
+		}
+
+		assert_noop!(TestModule::approve_spend(Origin::signed(Proposer::get()), 0), BadOrigin);
+		assert_noop!(
+			TestModule::approve_spend(Origin::signed(Admin::get()), 3),
+			Error::<Test>::UnknownProposal
+		);
+		assert_ok!(TestModule::approve_spend(Origin::signed(Admin::get()), 0));
+		assert_ok!(TestModule::approve_spend(RawOrigin::Root.into(), 1));
+		assert_noop!(
+			TestModule::approve_spend(Origin::signed(Admin::get()), 0),
+			Error::<Test>::AlreadyApproved
+		);
+
+		assert_noop!(TestModule::reject_spend(Origin::signed(Proposer::get()), 1), BadOrigin);
+		assert_ok!(TestModule::reject_spend(Origin::signed(Admin::get()), 1));
+		assert_ok!(TestModule::reject_spend(Origin::signed(Admin::get()), 2));
+		assert_noop!(
+			TestModule::reject_spend(Origin::signed(Admin::get()), 2),
+			Error::<Test>::UnknownProposal
+		);
+		assert_eq!(TestModule::approvals(), vec![0]);
+
+		TestModule::on_initialize(4);
+		assert_eq!(Balances::free_balance(3), 0);
+
+		TestModule::on_initialize(5);
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Balances::free_balance(4), 0);
+		assert_eq!(TestModule::proposals(0), None);
+		assert_eq!(TestModule::approvals(), Vec::<ProposalIndex>::new());
+		assert_eq!(TestModule::spent_this_period(), 100);
+	})
+}
+
+#[test]
+fn proposals_over_the_cap_wait_for_next_period() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 2_000);
+		for (beneficiary, amount) in [(3, 700), (4, 400), (5, 200)] {
+			assert_ok!(TestModule::propose_spend(
+				Origin::signed(Proposer::get()),
+				beneficiary,
+				amount,
+				Default::default()
+			));
+			assert_ok!(TestModule::approve_spend(
+				Origin::signed(Admin::get()),
+				TestModule::proposal_count() - 1
+			));
+		}
+		assert_noop!(
+			TestModule::approve_spend(Origin::signed(Admin::get()), 3),
+			Error::<Test>::UnknownProposal
+		);
+
+		TestModule::on_initialize(5);
+		assert_eq!(Balances::free_balance(3), 700);
+		assert_eq!(Balances::free_balance(4), 0);
+		assert_eq!(Balances::free_balance(5), 200);
+		assert_eq!(TestModule::approvals(), vec![1]);
+
+		TestModule::on_initialize(10);
+		assert_eq!(Balances::free_balance(4), 400);
+		assert_eq!(TestModule::approvals(), Vec::<ProposalIndex>::new());
+	})
+}
+
+#[test]
+fn approvals_are_bounded() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..4 {
+			assert_ok!(TestModule::propose_spend(
+				Origin::signed(Proposer::get()),
+				3,
+				100,
+				Default::default()
+			));
+		}
+		for proposal in 0..3 {
+			assert_ok!(TestModule::approve_spend(Origin::signed(Admin::get()), proposal));
+		}
+		assert_noop!(
+			TestModule::approve_spend(Origin::signed(Admin::get()), 3),
+			Error::<Test>::TooManyApprovals
+		);
+	})
+}
+
+#[test]
+fn failed_payments_stay_approved() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TestModule::propose_spend(
+			Origin::signed(Proposer::get()),
+			3,
+			100,
+			Default::default()
+		));
+		assert_ok!(TestModule::approve_spend(Origin::signed(Admin::get()), 0));
+
+		TestModule::on_initialize(5);
+		assert_eq!(Balances::free_balance(3), 0);
+		assert_eq!(TestModule::approvals(), vec![0]);
+		assert_eq!(TestModule::spent_this_period(), 0);
+
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 100);
+		TestModule::on_initialize(10);
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(TestModule::approvals(), Vec::<ProposalIndex>::new());
+	})
+}
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		TestModule::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn create_stream_checks_origin_and_schedule() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+
+		assert_noop!(
+			TestModule::create_stream(Origin::signed(0), 20, 100, 3, 6, 10, None),
+			BadOrigin
+		);
+		assert_noop!(
+			TestModule::create_stream(Origin::signed(Admin::get()), 20, 100, 0, 6, 10, None),
+			Error::<Test>::ZeroStreamPeriod
+		);
+		assert_noop!(
+			TestModule::create_stream(Origin::signed(Admin::get()), 20, 100, 3, 6, 5, None),
+			Error::<Test>::StreamEndsBeforeStart
+		);
+
+		// a start in the past begins at the next block
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			20,
+			100,
+			3,
+			1,
+			10,
+			None
+		));
+		assert_eq!(
+			TestModule::streams(0),
+			Some(PaymentStream {
+				beneficiary: 20,
+				amount: 100,
+				period: 3,
+				start: 6,
+				end: 10,
+				next_payment: 6,
+				paused: false,
+				canceller: None,
+			})
+		);
+		assert_eq!(TestModule::stream_agenda(6), vec![0]);
+	})
+}
+
+#[test]
+fn streams_pay_every_period_until_their_end() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 10_000);
+		assert_ok!(TestModule::create_stream(
+			RawOrigin::Root.into(),
+			20,
+			100,
+			3,
+			2,
+			8,
+			None
+		));
+
+		run_to_block(4);
+		assert_eq!(Balances::free_balance(20), 100);
+		assert_eq!(TestModule::streams(0).map(|stream| stream.next_payment), Some(5));
+
+		run_to_block(8);
+		assert_eq!(Balances::free_balance(20), 300);
+		assert_eq!(TestModule::streams(0), None);
+
+		run_to_block(12);
+		assert_eq!(Balances::free_balance(20), 300);
+	})
+}
+
+#[test]
+fn paused_streams_skip_payments() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 10_000);
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			20,
+			100,
+			3,
+			2,
+			8,
+			None
+		));
+		run_to_block(2);
+
+		assert_noop!(TestModule::pause_stream(Origin::signed(0), 0), BadOrigin);
+		assert_noop!(
+			TestModule::pause_stream(Origin::signed(Admin::get()), 1),
+			Error::<Test>::UnknownStream
+		);
+		assert_noop!(
+			TestModule::resume_stream(Origin::signed(Admin::get()), 0),
+			Error::<Test>::StreamNotPaused
+		);
+		assert_ok!(TestModule::pause_stream(Origin::signed(Admin::get()), 0));
+		assert_noop!(
+			TestModule::pause_stream(Origin::signed(Admin::get()), 0),
+			Error::<Test>::StreamAlreadyPaused
+		);
+
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(20), 100);
+
+		assert_ok!(TestModule::resume_stream(Origin::signed(Admin::get()), 0));
+		run_to_block(8);
+		assert_eq!(Balances::free_balance(20), 200);
+	})
+}
+
+#[test]
+fn cancelled_streams_stop_paying() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 10_000);
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			20,
+			100,
+			3,
+			2,
+			20,
+			None
+		));
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			21,
+			100,
+			3,
+			2,
+			20,
+			None
+		));
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			22,
+			100,
+			3,
+			2,
+			20,
+			Some(30)
+		));
+		run_to_block(2);
+
+		assert_noop!(TestModule::cancel_stream(Origin::signed(Proposer::get()), 0), BadOrigin);
+		assert_ok!(TestModule::cancel_stream(Origin::signed(StreamCanceller::get()), 0));
+		assert_ok!(TestModule::cancel_stream(Origin::signed(Admin::get()), 1));
+		assert_noop!(
+			TestModule::cancel_stream(RawOrigin::Root.into(), 1),
+			Error::<Test>::UnknownStream
+		);
+
+		// the canceller of a stream may only cancel that one
+		assert_noop!(TestModule::cancel_stream(Origin::signed(Proposer::get()), 2), BadOrigin);
+		assert_ok!(TestModule::cancel_stream(Origin::signed(30), 2));
+
+		run_to_block(20);
+		assert_eq!(Balances::free_balance(20), 100);
+		assert_eq!(Balances::free_balance(21), 100);
+		assert_eq!(Balances::free_balance(22), 100);
+	})
+}
+
+#[test]
+fn stream_payouts_are_bounded_per_block() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 10_000);
+		for beneficiary in 20..23 {
+			assert_ok!(TestModule::create_stream(
+				Origin::signed(Admin::get()),
+				beneficiary,
+				100,
+				10,
+				2,
+				20,
+				None
+			));
+		}
+
+		run_to_block(2);
+		assert_eq!(Balances::free_balance(20), 100);
+		assert_eq!(Balances::free_balance(21), 100);
+		assert_eq!(Balances::free_balance(22), 0);
+		assert_eq!(TestModule::stream_agenda(3), vec![2]);
+
+		run_to_block(3);
+		assert_eq!(Balances::free_balance(22), 100);
+		// the delayed payment does not shift the following ones
+		assert_eq!(TestModule::streams(2).map(|stream| stream.next_payment), Some(12));
+	})
+}
+
+#[test]
+fn stream_payments_count_towards_the_period_cap() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 10_000);
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			20,
+			600,
+			2,
+			1,
+			5,
+			None
+		));
+
+		run_to_block(1);
+		assert_noop!(
+			TestModule::spend(Origin::signed(Admin::get()), 3, 500),
+			Error::<Test>::PeriodCapExceeded
+		);
+
+		// the second payment would exceed the cap and is skipped, the new period allows
+		// the third one
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(20), 1_200);
+	})
+}
+
+#[test]
+fn spend_fungible_assets() {
+	new_test_ext().execute_with(|| {
+		TestAssets::set_balance(7, TestModule::account_id(), 100);
+
+		assert_noop!(TestModule::spend_asset(Origin::signed(0), 7, 3, 40), BadOrigin);
+		assert_noop!(
+			TestModule::spend_asset(Origin::signed(Admin::get()), 7, 3, 101),
+			TokenError::NoFunds
+		);
+		assert_ok!(TestModule::spend_asset(Origin::signed(Admin::get()), 7, 3, 40));
+		assert_eq!(TestAssets::balance(7, &3), 40);
+		assert_eq!(TestAssets::balance(7, &TestModule::account_id()), 60);
+		// only the native currency counts towards the cap
+		assert_eq!(TestModule::spent_this_period(), 0);
+	})
+}
+
+#[test]
+fn spend_nfts() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(RawOrigin::Root.into(), 0, 5, true));
+		assert_ok!(Uniques::mint(Origin::signed(5), 0, 42, TestModule::account_id()));
+		assert_ok!(Uniques::mint(Origin::signed(5), 0, 43, 5));
+
+		assert_noop!(TestModule::spend_nft(Origin::signed(0), 0, 42, 3), BadOrigin);
+		assert_noop!(
+			TestModule::spend_nft(Origin::signed(Admin::get()), 0, 43, 3),
+			Error::<Test>::NotNftOwner
+		);
+		assert_ok!(TestModule::spend_nft(Origin::signed(Admin::get()), 0, 42, 3));
+		assert_eq!(Uniques::owner(0, 42), Some(3));
+		assert_noop!(
+			TestModule::spend_nft(Origin::signed(Admin::get()), 0, 42, 3),
+			Error::<Test>::NotNftOwner
+		);
+	})
+}
+
+#[test]
+fn holdings_report_every_asset() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		TestAssets::set_balance(7, TestModule::account_id(), 100);
+		TestAssets::set_balance(8, TestModule::account_id(), 200);
+		assert_ok!(Uniques::force_create(RawOrigin::Root.into(), 0, 5, true));
+		assert_ok!(Uniques::mint(Origin::signed(5), 0, 42, TestModule::account_id()));
+
+		assert_noop!(TestModule::track_asset(Origin::signed(0), 7), BadOrigin);
+		assert_ok!(TestModule::track_asset(Origin::signed(Admin::get()), 7));
+		assert_noop!(
+			TestModule::track_asset(Origin::signed(Admin::get()), 7),
+			Error::<Test>::AssetAlreadyTracked
+		);
+		assert_ok!(TestModule::track_asset(Origin::signed(Admin::get()), 8));
+		assert_noop!(
+			TestModule::track_asset(Origin::signed(Admin::get()), 9),
+			Error::<Test>::TooManyTrackedAssets
+		);
+
+		assert_eq!(
+			TestModule::holdings(),
+			Holdings {
+				native: 1_000,
+				assets: vec![(7, 100), (8, 200)],
+				nfts: vec![(0, 42)],
+			}
+		);
+
+		assert_ok!(TestModule::untrack_asset(Origin::signed(Admin::get()), 7));
+		assert_noop!(
+			TestModule::untrack_asset(Origin::signed(Admin::get()), 7),
+			Error::<Test>::AssetNotTracked
+		);
+		assert_eq!(TestModule::holdings().assets, vec![(8, 200)]);
+	})
+}
+
+fn set_guardrails(max_per_spend: Option<u64>, max_per_window: Option<u64>, allowlist_only: bool) {
+	assert_ok!(TestModule::set_guardrails(
+		Origin::signed(GuardrailsAdmin::get()),
+		Guardrails {
+			max_per_spend,
+			max_per_window,
+			allowlist_only,
+		}
+	));
+}
+
+#[test]
+fn guardrails_can_only_be_set_by_their_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TestModule::set_guardrails(Origin::signed(Admin::get()), Default::default()),
+			BadOrigin
+		);
+		assert_noop!(
+			TestModule::allow_beneficiary(Origin::signed(Admin::get()), 3),
+			BadOrigin
+		);
+		assert_noop!(
+			TestModule::disallow_beneficiary(Origin::signed(Admin::get()), 3),
+			BadOrigin
+		);
+
+		set_guardrails(Some(100), None, false);
+		assert_eq!(TestModule::guardrails().max_per_spend, Some(100));
+		assert_ok!(TestModule::set_guardrails(RawOrigin::Root.into(), Default::default()));
+		assert_eq!(TestModule::guardrails(), Default::default());
+	})
+}
+
+#[test]
+fn spends_are_capped_by_max_per_spend() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		set_guardrails(Some(100), None, false);
+
+		assert_noop!(
+			TestModule::spend(Origin::signed(Admin::get()), 3, 101),
+			Error::<Test>::SpendAboveLimit
+		);
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 100));
+
+		assert_noop!(
+			TestModule::create_stream(Origin::signed(Admin::get()), 3, 101, 2, 1, 5, None),
+			Error::<Test>::SpendAboveLimit
+		);
+
+		assert_ok!(TestModule::propose_spend(
+			Origin::signed(Proposer::get()),
+			3,
+			101,
+			Default::default()
+		));
+		assert_noop!(
+			TestModule::approve_spend(Origin::signed(Admin::get()), 0),
+			Error::<Test>::SpendAboveLimit
+		);
+	})
+}
+
+#[test]
+fn spends_are_capped_over_the_rolling_window() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		set_guardrails(None, Some(300), false);
+
+		System::set_block_number(1);
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 200));
+		System::set_block_number(6);
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 100));
+		assert_noop!(
+			TestModule::spend(Origin::signed(Admin::get()), 3, 1),
+			Error::<Test>::WindowLimitExceeded
+		);
+		assert_eq!(TestModule::spent_in_window(), 300);
+
+		// the first spend leaves the window
+		System::set_block_number(11);
+		assert_eq!(TestModule::spent_in_window(), 100);
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 200));
+		assert_eq!(TestModule::recent_spends(), vec![(6, 100), (11, 200)]);
+	})
+}
+
+#[test]
+fn apply_as_outflows_count_towards_the_limits() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		set_guardrails(Some(250), Some(300), false);
+		let transfer = |value| {
+			Box::new(Call::Balances(pallet_balances::Call::<Test>::transfer_keep_alive {
+				dest: 3,
+				value,
+			}))
+		};
+
+		assert_noop!(
+			TestModule::apply_as(Origin::signed(Admin::get()), transfer(251)),
+			Error::<Test>::SpendAboveLimit
+		);
+		assert_ok!(TestModule::apply_as(Origin::signed(Admin::get()), transfer(200)));
+		assert_eq!(TestModule::spent_in_window(), 200);
+		assert_eq!(TestModule::spent_this_period(), 200);
+		assert_noop!(
+			TestModule::apply_as(Origin::signed(Admin::get()), transfer(101)),
+			Error::<Test>::WindowLimitExceeded
+		);
+
+		assert_eq!(Balances::free_balance(3), 200);
+		assert_eq!(TestModule::outflows(OutflowKind::ApplyAs), 200);
+	})
+}
+
+#[test]
+fn allowlist_restricts_beneficiaries() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		TestAssets::set_balance(7, TestModule::account_id(), 100);
+		set_guardrails(None, None, true);
+
+		assert_noop!(
+			TestModule::spend(Origin::signed(Admin::get()), 3, 10),
+			Error::<Test>::BeneficiaryNotAllowed
+		);
+		assert_noop!(
+			TestModule::spend_asset(Origin::signed(Admin::get()), 7, 3, 10),
+			Error::<Test>::BeneficiaryNotAllowed
+		);
+
+		assert_ok!(TestModule::allow_beneficiary(Origin::signed(GuardrailsAdmin::get()), 3));
+		assert_noop!(
+			TestModule::allow_beneficiary(Origin::signed(GuardrailsAdmin::get()), 3),
+			Error::<Test>::AlreadyAllowed
+		);
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 10));
+		assert_ok!(TestModule::spend_asset(Origin::signed(Admin::get()), 7, 3, 10));
+
+		assert_ok!(TestModule::disallow_beneficiary(
+			Origin::signed(GuardrailsAdmin::get()),
+			3
+		));
+		assert_noop!(
+			TestModule::disallow_beneficiary(Origin::signed(GuardrailsAdmin::get()), 3),
+			Error::<Test>::NotAllowed
+		);
+		assert_noop!(
+			TestModule::spend(Origin::signed(Admin::get()), 3, 10),
+			Error::<Test>::BeneficiaryNotAllowed
+		);
+	})
+}
+
+#[test]
+fn stream_payouts_check_the_allowlist() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		assert_ok!(TestModule::allow_beneficiary(
+			Origin::signed(GuardrailsAdmin::get()),
+			20
+		));
+		set_guardrails(None, None, true);
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			20,
+			100,
+			2,
+			1,
+			5,
+			None
+		));
+
+		run_to_block(1);
+		assert_eq!(Balances::free_balance(20), 100);
+
+		// payments are skipped while the beneficiary is out of the allowlist
+		assert_ok!(TestModule::disallow_beneficiary(
+			Origin::signed(GuardrailsAdmin::get()),
+			20
+		));
+		run_to_block(3);
+		assert_eq!(Balances::free_balance(20), 100);
+
+		assert_ok!(TestModule::allow_beneficiary(
+			Origin::signed(GuardrailsAdmin::get()),
+			20
+		));
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(20), 200);
+	})
+}
+
+#[test]
+fn approved_proposals_are_paid_within_the_current_guardrails() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		for (beneficiary, amount) in [(3, 100), (4, 200)] {
+			assert_ok!(TestModule::propose_spend(
+				Origin::signed(Proposer::get()),
+				beneficiary,
+				amount,
+				Default::default()
+			));
+			assert_ok!(TestModule::approve_spend(
+				Origin::signed(Admin::get()),
+				TestModule::proposal_count() - 1
+			));
+		}
+
+		set_guardrails(Some(150), None, true);
+		assert_ok!(TestModule::allow_beneficiary(Origin::signed(GuardrailsAdmin::get()), 4));
+		TestModule::on_initialize(5);
+		assert_eq!(Balances::free_balance(3), 0);
+		assert_eq!(Balances::free_balance(4), 0);
+		assert_eq!(TestModule::approvals(), vec![0, 1]);
+		assert_eq!(TestModule::spent_this_period(), 0);
+
+		set_guardrails(Some(200), None, false);
+		TestModule::on_initialize(10);
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Balances::free_balance(4), 200);
+		assert_eq!(TestModule::approvals(), Vec::<ProposalIndex>::new());
+	})
+}
+
+#[test]
+fn stream_payouts_check_the_max_per_spend() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			20,
+			100,
+			2,
+			1,
+			5,
+			None
+		));
+
+		run_to_block(1);
+		assert_eq!(Balances::free_balance(20), 100);
+
+		set_guardrails(Some(50), None, false);
+		run_to_block(3);
+		assert_eq!(Balances::free_balance(20), 100);
+
+		set_guardrails(None, None, false);
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(20), 200);
+	})
+}
+
+#[test]
+fn inflows_are_accounted_by_source() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&999, 100);
+
+		FeeInflow::<Test>::on_unbalanced(TestCurrency::issue(10));
+		DustInflow::<Test>::on_unbalanced(TestCurrency::issue(1));
+		TestModule::on_unbalanced(TestCurrency::issue(5));
+		TestModule::on_protocol_fee(20);
+		TestModule::on_expired_rewards(30);
+		assert_ok!(TestModule::tip(Origin::signed(999), 50));
+		// failed tips are not accounted
+		assert_noop!(
+			TestModule::tip(Origin::signed(999), 500),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+
+		assert_eq!(
+			TestModule::accounting().inflows,
+			vec![
+				(InflowSource::Fees, 10),
+				(InflowSource::Dust, 1),
+				(InflowSource::Tips, 50),
+				(InflowSource::ProtocolFees, 20),
+				(InflowSource::Other, 5),
+				(InflowSource::ExpiredRewards, 30),
+			]
+		);
+	})
+}
+
+#[test]
+fn outflows_are_accounted_by_kind() {
+	new_test_ext().execute_with(|| {
+		TestCurrency::make_free_balance_be(&TestModule::account_id(), 1_000);
+
+		assert_ok!(TestModule::spend(Origin::signed(Admin::get()), 3, 100));
+		assert_ok!(TestModule::propose_spend(
+			Origin::signed(Proposer::get()),
+			3,
+			200,
+			Default::default()
+		));
+		assert_ok!(TestModule::approve_spend(Origin::signed(Admin::get()), 0));
+		assert_ok!(TestModule::create_stream(
+			Origin::signed(Admin::get()),
+			20,
+			30,
+			10,
+			1,
+			20,
+			None
+		));
+		run_to_block(5);
+
+		assert_eq!(
+			TestModule::accounting().outflows,
+			vec![
+				(OutflowKind::Spends, 100),
+				(OutflowKind::Proposals, 200),
+				(OutflowKind::Streams, 30),
+				(OutflowKind::ApplyAs, 0),
+			]
+		);
+	})
+}
@@ -34,7 +34,7 @@ pub mod weights;
 
 use frame_support::pallet;
 pub(crate) mod hooks;
-mod migrations;
+pub mod migrations;
 pub(crate) mod slashing;
 pub(crate) mod types;
 
@@ -47,7 +47,6 @@ pub use pallet::*;
 pub mod pallet {
 	use super::*;
 	use crate::set::OrderedSet;
-	use frame_support::traits::OnRuntimeUpgrade;
 	use frame_support::{
 		pallet_prelude::*,
 		traits::{
@@ -140,21 +139,7 @@ pub mod pallet {
 	pub struct Pallet<T>(PhantomData<T>);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		#[cfg(feature = "try-runtime")]
-		fn pre_upgrade() -> Result<(), &'static str> {
-			migrations::v1::PoAToStaking::<T>::pre_upgrade()
-		}
-
-		fn on_runtime_upgrade() -> frame_support::weights::Weight {
-			migrations::v1::PoAToStaking::<T>::on_runtime_upgrade()
-		}
-
-		#[cfg(feature = "try-runtime")]
-		fn post_upgrade() -> Result<(), &'static str> {
-			migrations::v1::PoAToStaking::<T>::post_upgrade()
-		}
-	}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -157,6 +157,8 @@ pub mod v1 {
 
 		#[cfg(feature = "try-runtime")]
 		fn pre_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
 			log::info!(
 				"pre_upgrade[{:#?}]=>Validators.exits()? {:?}",
 				line!(),
@@ -172,16 +174,24 @@ pub mod v1 {
 				STORAGE_VERSION
 			);
 
+			// Already migrated, nothing left to check against.
+			if storage_version >= STORAGE_VERSION {
+				return Ok(());
+			}
+
 			// these must exist.
-			assert!(
-				<Validators<T>>::get().is_some(),
-				"Poa Validators storage item not found!"
-			);
+			let poa_validators = <Validators<T>>::get().ok_or("Poa Validators storage item not found!")?;
+
+			Self::set_temp_storage(poa_validators, "poa_validators");
+			Self::set_temp_storage(<Invulnerables<T>>::get(), "pre_invulnerables");
+
 			Ok(())
 		}
 
 		#[cfg(feature = "try-runtime")]
 		fn post_upgrade() -> Result<(), &'static str> {
+			use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
 			log::info!(
 				"post_upgrade[{:#?}]=>Validators.exits()? {:?}",
 				line!(),
@@ -198,10 +208,42 @@ pub mod v1 {
 			);
 
 			// should not exist.
-			assert!(
-				<Validators<T>>::get().is_none(),
-				"Poa Validators storage item not cleaned up!"
-			);
+			if <Validators<T>>::get().is_some() {
+				return Err("Poa Validators storage item not cleaned up!");
+			}
+
+			if storage_version != STORAGE_VERSION {
+				return Err("Staking storage version not bumped!");
+			}
+
+			let poa_validators: Vec<T::AccountId> = match Self::get_temp_storage("poa_validators") {
+				Some(poa_validators) => poa_validators,
+				// Nothing was migrated during this upgrade.
+				None => return Ok(()),
+			};
+			let pre_invulnerables: Vec<T::AccountId> =
+				Self::get_temp_storage("pre_invulnerables").ok_or("Pre upgrade invulnerables not found!")?;
+
+			let invulnerables = <Invulnerables<T>>::get();
+
+			if pre_invulnerables.iter().any(|acc| !invulnerables.contains(acc)) {
+				return Err("Pre upgrade invulnerables were dropped!");
+			}
+
+			for valid_acc in &poa_validators {
+				if !invulnerables.contains(valid_acc) {
+					return Err("Poa validator missing from the invulnerables!");
+				}
+
+				if !<ValidatorState<T>>::contains_key(valid_acc) {
+					return Err("Poa validator missing its validator state!");
+				}
+			}
+
+			if pre_invulnerables.is_empty() && <TotalSelected<T>>::get() != T::MinSelectedValidators::get() {
+				return Err("Staking configuration not initialized!");
+			}
+
 			Ok(())
 		}
 	}
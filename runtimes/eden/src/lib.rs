@@ -39,7 +39,7 @@ use frame_support::weights::Weight;
 
 use frame_support::construct_runtime;
 use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
-use primitives::{AccountId, Balance, BlockNumber, Index, Signature};
+use primitives::{AccountId, Balance, BlockNumber, Hash, Index, Signature};
 pub use primitives::{AuraId, ParaId};
 use sp_core::OpaqueMetadata;
 #[cfg(any(feature = "std", test))]
@@ -52,9 +52,11 @@ use sp_runtime::{
 };
 use sp_std::prelude::*;
 use sp_version::RuntimeVersion;
+use support::WithAccountId;
 
 pub mod constants;
 mod implementations;
+mod migrations;
 mod pallets_consensus;
 mod pallets_governance;
 mod pallets_nodle;
@@ -90,12 +92,15 @@ construct_runtime! {
 		Mandate: pallet_mandate::{Pallet, Call, Storage, Event<T>} = 15,
 		TechnicalCommittee: pallet_collective::<Instance1> = 16,
 		TechnicalMembership: pallet_membership::<Instance3> = 17,
+		Amendments: pallet_amendments = 18,
 
 		// Consensus
 		Authorship: pallet_authorship = 20,
-		ValidatorsSet: pallet_membership::<Instance1> = 21,
-		Poa: pallet_poa = 22,
+		// Staking must be declared before Session so that its genesis stakers exist when the
+		// first session is planned.
+		Staking: pallet_staking = 26,
 		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>} = 23,
+		Historical: pallet_session::historical::{Pallet, Storage} = 27,
 		Aura: pallet_aura::{Pallet, Config<T>, Storage} = 24,
 		AuraExt: cumulus_pallet_aura_ext::{Pallet, Config, Storage} = 25,
 
@@ -117,6 +122,7 @@ construct_runtime! {
 		EmergencyShutdown: pallet_emergency_shutdown = 50,
 		Allocations: pallet_allocations = 51,
 		AllocationsOracles: pallet_membership::<Instance2> = 52,
+		EmergencyGuardians: pallet_membership::<Instance4> = 53,
 	}
 }
 
@@ -147,8 +153,22 @@ pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 
-pub type Executive =
-	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPalletsWithSystem>;
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (
+	pallet_staking::migrations::v1::PoAToStaking<Runtime>,
+	migrations::RemovePoaPallets,
+	pallet_allocations::migrations::v1::CreateDefaultProgram<Runtime>,
+	pallet_mandate::migrations::v1::SeedAllowedCalls<Runtime, migrations::MandateAllowlistSeed>,
+);
+/// Executive: handles dispatch to the various modules.
+pub type Executive = frame_executive::Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllPalletsWithSystem,
+	Migrations,
+>;
 
 sp_api::impl_runtime_apis! {
 	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
@@ -249,6 +269,131 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_reserve_rpc_runtime_api::ReserveApi<Block, AccountId, Balance, u32, u128, u32, u32> for Runtime {
+		fn reserves() -> Vec<AccountId> {
+			vec![
+				CompanyReserve::account_id(),
+				InternationalReserve::account_id(),
+				UsaReserve::account_id(),
+			]
+		}
+
+		fn accounting(reserve: AccountId) -> Option<pallet_reserve_rpc_runtime_api::ReserveAccounting<Balance>> {
+			if reserve == CompanyReserve::account_id() {
+				Some(CompanyReserve::accounting())
+			} else if reserve == InternationalReserve::account_id() {
+				Some(InternationalReserve::accounting())
+			} else if reserve == UsaReserve::account_id() {
+				Some(UsaReserve::accounting())
+			} else {
+				None
+			}
+		}
+
+		fn holdings(
+			reserve: AccountId,
+		) -> Option<pallet_reserve_rpc_runtime_api::Holdings<Balance, u32, u128, u32, u32>> {
+			if reserve == CompanyReserve::account_id() {
+				Some(CompanyReserve::holdings())
+			} else if reserve == InternationalReserve::account_id() {
+				Some(InternationalReserve::holdings())
+			} else if reserve == UsaReserve::account_id() {
+				Some(UsaReserve::holdings())
+			} else {
+				None
+			}
+		}
+	}
+
+	impl pallet_mandate_rpc_runtime_api::MandateApi<Block, Hash, BlockNumber> for Runtime {
+		fn scheduled_mandates() -> Vec<(
+			pallet_mandate_rpc_runtime_api::MandateId,
+			pallet_mandate_rpc_runtime_api::ScheduledMandate<Hash, BlockNumber>,
+		)> {
+			Mandate::scheduled()
+		}
+
+		fn mandate_history() -> Vec<pallet_mandate_rpc_runtime_api::MandateExecution<Hash, BlockNumber>> {
+			Mandate::history()
+		}
+	}
+
+	impl pallet_amendments_rpc_runtime_api::AmendmentsApi<Block, OriginCaller, Hash, BlockNumber, Event> for Runtime {
+		fn pending_amendments() -> Vec<(
+			pallet_amendments_rpc_runtime_api::AmendmentId,
+			pallet_amendments_rpc_runtime_api::PendingAmendment<OriginCaller, Hash, BlockNumber>,
+		)> {
+			Amendments::pending()
+		}
+
+		fn vetoed_amendments() -> Vec<(
+			pallet_amendments_rpc_runtime_api::AmendmentId,
+			pallet_amendments_rpc_runtime_api::PendingAmendment<OriginCaller, Hash, BlockNumber>,
+			BlockNumber,
+		)> {
+			Amendments::vetoed()
+		}
+
+		fn dry_run(
+			amendment: Vec<u8>,
+		) -> Result<pallet_amendments_rpc_runtime_api::DryRun<Event>, sp_runtime::DispatchError> {
+			Amendments::dry_run(&amendment)
+		}
+	}
+
+	impl pallet_emergency_shutdown_rpc_runtime_api::EmergencyShutdownApi<
+		Block,
+		AccountId,
+		BlockNumber,
+		pallet_emergency_shutdown::ShutdownScopeOf<Runtime>,
+		pallet_emergency_shutdown::ShutdownReasonOf<Runtime>,
+	> for Runtime {
+		fn is_shutdown() -> bool {
+			EmergencyShutdown::shutdown()
+		}
+
+		fn open_incidents() -> Vec<(
+			pallet_emergency_shutdown_rpc_runtime_api::IncidentId,
+			pallet_emergency_shutdown::IncidentOf<Runtime>,
+		)> {
+			EmergencyShutdown::open_incidents()
+		}
+
+		fn incident_log(
+			from: pallet_emergency_shutdown_rpc_runtime_api::IncidentId,
+			count: u32,
+		) -> Vec<(
+			pallet_emergency_shutdown_rpc_runtime_api::IncidentId,
+			pallet_emergency_shutdown::IncidentOf<Runtime>,
+		)> {
+			EmergencyShutdown::incident_log(from, count)
+		}
+	}
+
+	impl pallet_allocations_rpc_runtime_api::AllocationsApi<Block, AccountId, BlockNumber, Balance> for Runtime {
+		fn emission_allowance() -> Balance {
+			Allocations::emission_allowance()
+		}
+
+		fn emission_headroom() -> Balance {
+			Allocations::emission_headroom()
+		}
+
+		fn recipient_totals(
+			who: AccountId,
+			epoch: Option<BlockNumber>,
+		) -> pallet_allocations_rpc_runtime_api::AllocationTotals<BlockNumber, Balance> {
+			Allocations::recipient_totals(&who, epoch)
+		}
+
+		fn oracle_totals(
+			oracle: AccountId,
+			epoch: Option<BlockNumber>,
+		) -> pallet_allocations_rpc_runtime_api::AllocationTotals<BlockNumber, Balance> {
+			Allocations::oracle_totals(&oracle, epoch)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -279,11 +424,14 @@ sp_api::impl_runtime_apis! {
 			list_benchmark!(list, extra, pallet_preimage, Preimage);
 			list_benchmark!(list, extra, pallet_multisig, Multisig);
 			list_benchmark!(list, extra, pallet_reserve, CompanyReserve);
+			list_benchmark!(list, extra, pallet_mandate, Mandate);
+			list_benchmark!(list, extra, pallet_amendments, Amendments);
 			list_benchmark!(list, extra, pallet_grants, Vesting);
 			list_benchmark!(list, extra, pallet_uniques, Uniques);
 			list_benchmark!(list, extra, pallet_utility, Utility);
 			list_benchmark!(list, extra, pallet_emergency_shutdown, EmergencyShutdown);
 			list_benchmark!(list, extra, pallet_allocations, Allocations);
+			list_benchmark!(list, extra, pallet_staking, Staking);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -314,11 +462,14 @@ sp_api::impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_preimage, Preimage);
 			add_benchmark!(params, batches, pallet_multisig, Multisig);
 			add_benchmark!(params, batches, pallet_reserve, CompanyReserve);
+			add_benchmark!(params, batches, pallet_mandate, Mandate);
+			add_benchmark!(params, batches, pallet_amendments, Amendments);
 			add_benchmark!(params, batches, pallet_grants, Vesting);
 			add_benchmark!(params, batches, pallet_uniques, Uniques);
 			add_benchmark!(params, batches, pallet_utility, Utility);
 			add_benchmark!(params, batches, pallet_emergency_shutdown, EmergencyShutdown);
 			add_benchmark!(params, batches, pallet_allocations, Allocations);
+			add_benchmark!(params, batches, pallet_staking, Staking);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
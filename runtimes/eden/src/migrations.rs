@@ -0,0 +1,155 @@
+/*
+ * This file is part of the Nodle Chain distributed at https://github.com/NodleCode/chain
+ * Copyright (C) 2020-2022  Nodle International
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Storage migrations of the runtime, applied on upgrade before the hooks of the pallets.
+
+use crate::{Call, Runtime};
+use frame_support::{
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+use sp_io::{hashing::twox_128, KillStorageResult};
+use sp_std::prelude::*;
+
+/// Pallets dropped from the runtime when moving from PoA to staking.
+const REMOVED_PALLETS: [&[u8]; 2] = [b"ValidatorsSet", b"Poa"];
+
+/// Clears whatever storage the `ValidatorsSet` and `Poa` pallets left behind. Must run after
+/// `PoAToStaking`, which reads the PoA validators.
+pub struct RemovePoaPallets;
+impl OnRuntimeUpgrade for RemovePoaPallets {
+	fn on_runtime_upgrade() -> Weight {
+		let mut removed: u64 = 0;
+		for pallet in REMOVED_PALLETS {
+			match sp_io::storage::clear_prefix(&twox_128(pallet), None) {
+				KillStorageResult::AllRemoved(n) | KillStorageResult::SomeRemaining(n) => removed += n as u64,
+			}
+		}
+
+		<Runtime as frame_system::Config>::DbWeight::get().writes(removed)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		if REMOVED_PALLETS.iter().any(|pallet| has_storage(pallet)) {
+			return Err("Storage of the PoA pallets not cleaned up!");
+		}
+		Ok(())
+	}
+}
+
+/// Calls the mandate may still dispatch once it is restricted to its allowlist, so that the
+/// runtime can keep being upgraded through it. Only their pallet and call indices matter.
+pub struct MandateAllowlistSeed;
+impl Get<Vec<Call>> for MandateAllowlistSeed {
+	fn get() -> Vec<Call> {
+		vec![
+			Call::System(frame_system::Call::set_code { code: Vec::new() }),
+			Call::ParachainSystem(cumulus_pallet_parachain_system::Call::authorize_upgrade {
+				code_hash: Default::default(),
+			}),
+		]
+	}
+}
+
+/// Whether any key is stored under the prefix of `pallet`.
+#[cfg(any(test, feature = "try-runtime"))]
+fn has_storage(pallet: &[u8]) -> bool {
+	let prefix = twox_128(pallet);
+	sp_io::storage::next_key(&prefix).map_or(false, |key| key.starts_with(&prefix))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Mandate, Migrations, Staking};
+	use frame_support::{storage::migration::put_storage_value, traits::StorageVersion};
+	use primitives::AccountId;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn poa_validators_are_moved_to_staking() {
+		new_test_ext().execute_with(|| {
+			let validators = vec![AccountId::new([1; 32]), AccountId::new([2; 32])];
+			put_storage_value(b"Poa", b"Validators", b"", validators.clone());
+			put_storage_value(b"ValidatorsSet", b"Members", b"", validators.clone());
+			put_storage_value(b"ValidatorsSet", b"Prime", b"", validators[0].clone());
+
+			assert!(has_storage(b"Poa"));
+			assert!(has_storage(b"ValidatorsSet"));
+			assert_eq!(StorageVersion::get::<Staking>(), 0);
+			assert!(Staking::invulnerables().is_empty());
+
+			#[cfg(feature = "try-runtime")]
+			assert_eq!(Migrations::pre_upgrade(), Ok(()));
+
+			Migrations::on_runtime_upgrade();
+
+			#[cfg(feature = "try-runtime")]
+			assert_eq!(Migrations::post_upgrade(), Ok(()));
+
+			assert_eq!(Staking::invulnerables(), validators);
+			assert!(validators
+				.iter()
+				.all(|validator| Staking::validator_state(validator).is_some()));
+			assert_eq!(StorageVersion::get::<Staking>(), 1);
+
+			assert!(!has_storage(b"Poa"));
+			assert!(!has_storage(b"ValidatorsSet"));
+		});
+	}
+
+	#[test]
+	fn migrations_are_a_noop_once_applied() {
+		new_test_ext().execute_with(|| {
+			let validators = vec![AccountId::new([1; 32])];
+			put_storage_value(b"Poa", b"Validators", b"", validators.clone());
+			Migrations::on_runtime_upgrade();
+
+			#[cfg(feature = "try-runtime")]
+			assert_eq!(Migrations::pre_upgrade(), Ok(()));
+
+			Migrations::on_runtime_upgrade();
+
+			#[cfg(feature = "try-runtime")]
+			assert_eq!(Migrations::post_upgrade(), Ok(()));
+
+			assert_eq!(Staking::invulnerables(), validators);
+		});
+	}
+
+	#[test]
+	fn mandate_allowlist_is_seeded() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(StorageVersion::get::<Mandate>(), 0);
+
+			Migrations::on_runtime_upgrade();
+
+			assert_eq!(StorageVersion::get::<Mandate>(), 1);
+			assert!(MandateAllowlistSeed::get()
+				.iter()
+				.all(|call| Mandate::allowed_calls(Mandate::call_indices(call)).is_some()));
+		});
+	}
+}
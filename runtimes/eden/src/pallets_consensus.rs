@@ -16,10 +16,14 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use crate::{constants, pallets_governance::MoreThanHalfOfTechComm, Aura, Event, Poa, Runtime};
-use frame_support::parameter_types;
-use primitives::{AccountId, AuraId};
-use sp_runtime::{impl_opaque_keys, traits::ConvertInto};
+use crate::{
+	constants, pallets_governance::MoreThanHalfOfTechComm, Aura, Balances, CompanyReserve, Event, Runtime, Session,
+	Staking,
+};
+use frame_support::{parameter_types, traits::LockIdentifier, PalletId};
+use primitives::{AccountId, AuraId, Balance};
+use sp_runtime::{impl_opaque_keys, Perbill};
+use sp_staking::SessionIndex;
 use sp_std::prelude::*;
 
 impl_opaque_keys! {
@@ -36,7 +40,7 @@ impl pallet_authorship::Config for Runtime {
 	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
 	type UncleGenerations = UncleGenerations;
 	type FilterUncle = ();
-	type EventHandler = ();
+	type EventHandler = Staking;
 }
 
 parameter_types! {
@@ -45,34 +49,66 @@ parameter_types! {
 }
 
 impl pallet_session::Config for Runtime {
-	type SessionManager = Poa;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, Staking>;
 	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
 	type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
 	type Event = Event;
 	type SessionHandler = <SessionKeys as sp_runtime::traits::OpaqueKeys>::KeyTypeIdProviders;
 	type Keys = SessionKeys;
 	type ValidatorId = AccountId;
-	type ValidatorIdOf = ConvertInto;
+	type ValidatorIdOf = pallet_staking::StashOf<Self>;
 	type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
 }
 
-impl pallet_poa::Config for Runtime {}
+impl pallet_session::historical::Config for Runtime {
+	type FullIdentification = pallet_staking::ValidatorSnapshot<AccountId, Balance>;
+	type FullIdentificationOf = pallet_staking::ValidatorSnapshotOf<Self>;
+}
 
 parameter_types! {
-	pub const MaxMembers: u32 = 50;
+	pub const BondedDuration: SessionIndex = 28; // 7 days of 6 hours sessions
+	pub const SlashDeferDuration: SessionIndex = 7; // 1 day and 18 hours
+	pub const MinSelectedValidators: u32 = 5;
+	pub const MaxNominatorsPerValidator: u32 = 25;
+	pub const MaxValidatorPerNominator: u32 = 25;
+	pub const DefaultValidatorFee: Perbill = Perbill::from_percent(20);
+	pub const DefaultSlashRewardProportion: Perbill = Perbill::from_percent(10);
+	pub const DefaultSlashRewardFraction: Perbill = Perbill::from_percent(50);
+	pub const DefaultStakingMaxValidators: u32 = 50;
+	pub const DefaultStakingMinStakeSessionSelection: Balance = 10 * constants::NODL;
+	pub const DefaultStakingMinValidatorBond: Balance = 10 * constants::NODL;
+	pub const DefaultStakingMinNominatorTotalBond: Balance = 10 * constants::NODL;
+	pub const DefaultStakingMinNominationChillThreshold: Balance = 3 * constants::NODL;
+	pub const MaxChunkUnlock: usize = 32;
+	pub const StakingPalletId: PalletId = PalletId(*b"py/stake"); // 5EYCAe5ijiYfqsKUNd9ZcfS4WKaQoBNoiQCaoWFiwPTdYmDh
+	pub const StakingLockId: LockIdentifier = *b"staking ";
 }
 
-impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+impl pallet_staking::Config for Runtime {
 	type Event = Event;
-	type AddOrigin = MoreThanHalfOfTechComm;
-	type RemoveOrigin = MoreThanHalfOfTechComm;
-	type SwapOrigin = MoreThanHalfOfTechComm;
-	type ResetOrigin = MoreThanHalfOfTechComm;
-	type PrimeOrigin = MoreThanHalfOfTechComm;
-	type MembershipInitialized = Poa;
-	type MembershipChanged = Poa;
-	type MaxMembers = MaxMembers;
-	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type Slash = CompanyReserve;
+	type BondedDuration = BondedDuration;
+	type SlashDeferDuration = SlashDeferDuration;
+	type MinSelectedValidators = MinSelectedValidators;
+	type MaxNominatorsPerValidator = MaxNominatorsPerValidator;
+	type MaxValidatorPerNominator = MaxValidatorPerNominator;
+	type DefaultValidatorFee = DefaultValidatorFee;
+	type DefaultSlashRewardProportion = DefaultSlashRewardProportion;
+	type DefaultSlashRewardFraction = DefaultSlashRewardFraction;
+	type DefaultStakingMaxValidators = DefaultStakingMaxValidators;
+	type DefaultStakingMinStakeSessionSelection = DefaultStakingMinStakeSessionSelection;
+	type DefaultStakingMinValidatorBond = DefaultStakingMinValidatorBond;
+	type DefaultStakingMinNominationChillThreshold = DefaultStakingMinNominationChillThreshold;
+	type DefaultStakingMinNominatorTotalBond = DefaultStakingMinNominatorTotalBond;
+	type RewardRemainder = CompanyReserve;
+	type SessionInterface = Self;
+	type ValidatorRegistration = Session;
+	type PalletId = StakingPalletId;
+	type StakingLockId = StakingLockId;
+	type MaxChunkUnlock = MaxChunkUnlock;
+	type CancelOrigin = MoreThanHalfOfTechComm;
+	type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
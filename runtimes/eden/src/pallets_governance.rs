@@ -16,25 +16,88 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use crate::{constants, Call, Event, Origin, Runtime, TechnicalCommittee};
-use frame_support::{parameter_types, PalletId};
-use primitives::{AccountId, BlockNumber};
+use crate::{
+	constants, Call, Event, Origin, OriginCaller, Preimage, Runtime, Scheduler, TechnicalCommittee,
+	TechnicalMembership, Uniques,
+};
+use frame_support::{parameter_types, traits::Contains, PalletId};
+use frame_system::EnsureSignedBy;
+use primitives::{AccountId, Balance, BlockNumber};
 pub use sp_runtime::{Perbill, Perquintill};
 
 parameter_types! {
+	pub const ReserveSpendPeriod: BlockNumber = 7 * constants::DAYS;
+	pub const MaxSpendDescriptionLength: u32 = 256;
+	pub const MaxReserveApprovals: u32 = 50;
+	pub const MaxStreamPayoutsPerBlock: u32 = 20;
+	pub const MaxTrackedAssets: u32 = 20;
+	pub const ReserveSpendWindow: BlockNumber = 30 * constants::DAYS;
+}
+
+/// Calls the reserves may dispatch through `apply_as`. Only calls that can not pay out the
+/// reserve to an arbitrary account are listed, funds have to leave through `spend` and the
+/// other calls of the reserves so that their guardrails apply.
+pub struct ReserveApplyAsFilter;
+impl Contains<Call> for ReserveApplyAsFilter {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::System(frame_system::Call::remark { .. } | frame_system::Call::remark_with_event { .. })
+				| Call::Uniques(
+					pallet_uniques::Call::freeze { .. }
+						| pallet_uniques::Call::thaw { .. }
+						| pallet_uniques::Call::freeze_class { .. }
+						| pallet_uniques::Call::thaw_class { .. }
+						| pallet_uniques::Call::set_attribute { .. }
+						| pallet_uniques::Call::clear_attribute { .. }
+						| pallet_uniques::Call::set_metadata { .. }
+						| pallet_uniques::Call::clear_metadata { .. }
+						| pallet_uniques::Call::set_class_metadata { .. }
+						| pallet_uniques::Call::clear_class_metadata { .. }
+				)
+		)
+	}
+}
+
+parameter_types! {
+	pub const CompanyReserveMaxSpendPerPeriod: Balance = 5_000_000 * constants::NODL;
 	pub const CompanyReservePalletId: PalletId = PalletId(*b"py/resrv"); // 5EYCAe5ijiYfha9GzQDgPVtUCYDY9B8ZgcyiANL2L34crMoR
 }
 
+// The runtime has no fungible asset besides NODL yet, the holdings of the reserves only ever
+// report their native balance and NFTs. Benchmarks spend NODL through the assets interface so
+// that `spend_asset` is weighed on a successful transfer.
+#[cfg(not(feature = "runtime-benchmarks"))]
+type ReserveAssets = pallet_reserve::NoAssets;
+#[cfg(feature = "runtime-benchmarks")]
+type ReserveAssets = pallet_reserve::NativeAssets<pallet_balances::Pallet<Runtime>>;
+
 impl pallet_reserve::Config<pallet_reserve::Instance1> for Runtime {
 	type Event = Event;
 	type Currency = pallet_balances::Pallet<Runtime>;
 	type ExternalOrigin = MoreThanHalfOfTechComm;
 	type Call = Call;
 	type PalletId = CompanyReservePalletId;
+	type ProposeOrigin = EnsureSignedBy<TechnicalMembership, AccountId>;
+	type SpendPeriod = ReserveSpendPeriod;
+	type MaxSpendPerPeriod = CompanyReserveMaxSpendPerPeriod;
+	type MaxDescriptionLength = MaxSpendDescriptionLength;
+	type MaxApprovals = MaxReserveApprovals;
+	type StreamCancelOrigin = MoreThanHalfOfTechComm;
+	type MaxStreamPayoutsPerBlock = MaxStreamPayoutsPerBlock;
+	type Assets = ReserveAssets;
+	type MaxTrackedAssets = MaxTrackedAssets;
+	type NftClassId = u32;
+	type NftInstanceId = u32;
+	type Uniques = Uniques;
+	type GuardrailsOrigin = TwoThirdsOfTechComm;
+	type SpendWindow = ReserveSpendWindow;
+	type ApplyAsFilter = ReserveApplyAsFilter;
 	type WeightInfo = pallet_reserve::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
+	pub const InternationalReserveMaxSpendPerPeriod: Balance = 1_000_000 * constants::NODL;
 	pub const InternationalReservePalletId: PalletId = PalletId(*b"py/rvint"); // 5EYCAe5ijiYfi6GQAEPSHYDwvw4CkyGtPTS52BjLh42GygSv
 }
 
@@ -44,10 +107,26 @@ impl pallet_reserve::Config<pallet_reserve::Instance2> for Runtime {
 	type ExternalOrigin = MoreThanHalfOfTechComm;
 	type Call = Call;
 	type PalletId = InternationalReservePalletId;
+	type ProposeOrigin = EnsureSignedBy<TechnicalMembership, AccountId>;
+	type SpendPeriod = ReserveSpendPeriod;
+	type MaxSpendPerPeriod = InternationalReserveMaxSpendPerPeriod;
+	type MaxDescriptionLength = MaxSpendDescriptionLength;
+	type MaxApprovals = MaxReserveApprovals;
+	type StreamCancelOrigin = MoreThanHalfOfTechComm;
+	type MaxStreamPayoutsPerBlock = MaxStreamPayoutsPerBlock;
+	type Assets = ReserveAssets;
+	type MaxTrackedAssets = MaxTrackedAssets;
+	type NftClassId = u32;
+	type NftInstanceId = u32;
+	type Uniques = Uniques;
+	type GuardrailsOrigin = TwoThirdsOfTechComm;
+	type SpendWindow = ReserveSpendWindow;
+	type ApplyAsFilter = ReserveApplyAsFilter;
 	type WeightInfo = pallet_reserve::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
+	pub const UsaReserveMaxSpendPerPeriod: Balance = 1_000_000 * constants::NODL;
 	pub const UsaReservePalletId: PalletId = PalletId(*b"py/rvusa"); // 5EYCAe5ijiYfi6MEfWpZC3nJ38KFZ9EQSFpsj9mgYgTtVNri
 }
 
@@ -57,6 +136,21 @@ impl pallet_reserve::Config<pallet_reserve::Instance3> for Runtime {
 	type ExternalOrigin = MoreThanHalfOfTechComm;
 	type Call = Call;
 	type PalletId = UsaReservePalletId;
+	type ProposeOrigin = EnsureSignedBy<TechnicalMembership, AccountId>;
+	type SpendPeriod = ReserveSpendPeriod;
+	type MaxSpendPerPeriod = UsaReserveMaxSpendPerPeriod;
+	type MaxDescriptionLength = MaxSpendDescriptionLength;
+	type MaxApprovals = MaxReserveApprovals;
+	type StreamCancelOrigin = MoreThanHalfOfTechComm;
+	type MaxStreamPayoutsPerBlock = MaxStreamPayoutsPerBlock;
+	type Assets = ReserveAssets;
+	type MaxTrackedAssets = MaxTrackedAssets;
+	type NftClassId = u32;
+	type NftInstanceId = u32;
+	type Uniques = Uniques;
+	type GuardrailsOrigin = TwoThirdsOfTechComm;
+	type SpendWindow = ReserveSpendWindow;
+	type ApplyAsFilter = ReserveApplyAsFilter;
 	type WeightInfo = pallet_reserve::weights::SubstrateWeight<Runtime>;
 }
 
@@ -68,6 +162,8 @@ parameter_types! {
 
 pub type MoreThanHalfOfTechComm =
 	pallet_collective::EnsureProportionMoreThan<AccountId, pallet_collective::Instance1, 1, 2>;
+pub type TwoThirdsOfTechComm =
+	pallet_collective::EnsureProportionAtLeast<AccountId, pallet_collective::Instance1, 2, 3>;
 impl pallet_collective::Config<pallet_collective::Instance1> for Runtime {
 	type Origin = Origin;
 	type Proposal = Call;
@@ -92,8 +188,62 @@ impl pallet_membership::Config<pallet_membership::Instance3> for Runtime {
 	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxMandateHistoryLength: u32 = 100;
+	pub const MaxMandateBatchedCalls: u32 = 20;
+}
+
+/// Calls dispatching other calls, the mandate refuses them so that the calls they wrap can
+/// not escape its allowlist.
+pub struct MandateCallWrappers;
+impl Contains<Call> for MandateCallWrappers {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::Utility(..)
+				| Call::Multisig(..)
+				| Call::Scheduler(..)
+				| Call::Mandate(..)
+				| Call::Amendments(..)
+				| Call::TechnicalCommittee(..)
+				| Call::CompanyReserve(pallet_reserve::Call::apply_as { .. })
+				| Call::InternationalReserve(pallet_reserve::Call::apply_as { .. })
+				| Call::UsaReserve(pallet_reserve::Call::apply_as { .. })
+		)
+	}
+}
+
 impl pallet_mandate::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
 	type ExternalOrigin = MoreThanHalfOfTechComm;
+	type AllowlistOrigin = TwoThirdsOfTechComm;
+	type MaxHistoryLength = MaxMandateHistoryLength;
+	type MaxBatchedCalls = MaxMandateBatchedCalls;
+	type CallWrappers = MandateCallWrappers;
+	type WeightInfo = pallet_mandate::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const AmendmentDelay: BlockNumber = 2 * constants::DAYS;
+	pub const AmendmentFastTrackDelay: BlockNumber = 6 * constants::HOURS;
+	pub const AmendmentVetoOverrideWindow: BlockNumber = 2 * constants::DAYS;
+}
+
+impl pallet_amendments::Config for Runtime {
+	type Event = Event;
+	type Amendment = Call;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type SubmissionOrigin = MoreThanHalfOfTechComm;
+	type VetoOrigin = EnsureSignedBy<TechnicalMembership, AccountId>;
+	type Delay = AmendmentDelay;
+	type Preimages = Preimage;
+	type FastTrackOrigin = TwoThirdsOfTechComm;
+	type FastTrackDelay = AmendmentFastTrackDelay;
+	type VetoOverrideOrigin = TwoThirdsOfTechComm;
+	type VetoOverrideWindow = AmendmentVetoOverrideWindow;
+	type WeightInfo = pallet_amendments::weights::SubstrateWeight<Runtime>;
 }
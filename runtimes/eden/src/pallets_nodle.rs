@@ -15,21 +15,105 @@
  * You should have received a copy of the GNU General Public License
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
-use crate::{pallets_governance::MoreThanHalfOfTechComm, Allocations, Balances, CompanyReserve, Event, Runtime};
-use frame_support::{parameter_types, PalletId};
-use primitives::Balance;
+use crate::{
+	constants::{DAYS, HOURS},
+	implementations::RelayChainBlockNumberProvider,
+	pallets_governance::{MoreThanHalfOfTechComm, TwoThirdsOfTechComm},
+	Allocations, Balances, CompanyReserve, EmergencyGuardians, Event, InternationalReserve, Multisig, Runtime, Staking,
+	Uniques, UsaReserve, Utility, Vesting,
+};
+use frame_support::{
+	parameter_types,
+	traits::{Contains, PalletInfoAccess},
+	PalletId,
+};
+use frame_system::EnsureSignedBy;
+use primitives::{AccountId, Balance, BlockNumber};
 use sp_runtime::Perbill;
+use sp_std::prelude::*;
+
+parameter_types! {
+	pub const MaxShutdownReasonLength: u32 = 256;
+	pub const MaxShutdownNameLength: u32 = 64;
+}
+
+/// Pallets whose calls may be paused. Pausing the pallets needed to author blocks, or the
+/// ones governing the chain, could leave no way to lift the pause.
+pub struct PausablePallets;
+impl Contains<Vec<u8>> for PausablePallets {
+	fn contains(pallet_name: &Vec<u8>) -> bool {
+		[
+			Balances::name(),
+			Vesting::name(),
+			Staking::name(),
+			Utility::name(),
+			Multisig::name(),
+			Uniques::name(),
+			Allocations::name(),
+			CompanyReserve::name(),
+			InternationalReserve::name(),
+			UsaReserve::name(),
+		]
+		.iter()
+		.any(|name| name.as_bytes() == pallet_name.as_slice())
+	}
+}
+
+/// Guardians may pause single calls, or the whole pallet, of the pallets minting or moving
+/// reserve funds.
+pub struct GuardianScopes;
+impl Contains<pallet_emergency_shutdown::ShutdownScopeOf<Runtime>> for GuardianScopes {
+	fn contains(scope: &pallet_emergency_shutdown::ShutdownScopeOf<Runtime>) -> bool {
+		[
+			Allocations::name(),
+			CompanyReserve::name(),
+			InternationalReserve::name(),
+			UsaReserve::name(),
+		]
+		.iter()
+		.any(|name| name.as_bytes() == scope.pallet_name())
+	}
+}
 
 impl pallet_emergency_shutdown::Config for Runtime {
 	type Event = Event;
 	type ShutdownOrigin = MoreThanHalfOfTechComm;
+	type GuardianOrigin = EnsureSignedBy<EmergencyGuardians, AccountId>;
+	type GuardianScopes = GuardianScopes;
+	type MaxReasonLength = MaxShutdownReasonLength;
+	type MaxNameLength = MaxShutdownNameLength;
+	type PausablePallets = PausablePallets;
 	type WeightInfo = pallet_emergency_shutdown::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxGuardians: u32 = 5;
+}
+
+/// Dedicated keys, or multisig accounts, allowed to engage a shutdown without a vote of the
+/// technical committee.
+impl pallet_membership::Config<pallet_membership::Instance4> for Runtime {
+	type Event = Event;
+	type AddOrigin = TwoThirdsOfTechComm;
+	type RemoveOrigin = MoreThanHalfOfTechComm;
+	type SwapOrigin = TwoThirdsOfTechComm;
+	type ResetOrigin = TwoThirdsOfTechComm;
+	type PrimeOrigin = TwoThirdsOfTechComm;
+	type MembershipInitialized = ();
+	type MembershipChanged = ();
+	type MaxMembers = MaxGuardians;
+	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
 	pub const ProtocolFee: Perbill = Perbill::from_percent(20);
 	pub const MaximumCoinsEverAllocated: Balance = 1_259_995_654_473_120_000_000;
 	pub const AllocPalletId: PalletId = PalletId(*b"py/alloc");
+	pub const MaxAllocs: u32 = 500;
+	pub const MaxProofDepth: u32 = 32;
+	pub const ClaimPeriod: BlockNumber = 30 * DAYS;
+	pub const QuorumVotingPeriod: BlockNumber = 12 * HOURS;
+	pub const AccountingEpoch: BlockNumber = 7 * DAYS;
 }
 
 impl pallet_allocations::Config for Runtime {
@@ -39,7 +123,19 @@ impl pallet_allocations::Config for Runtime {
 	type ProtocolFee = ProtocolFee;
 	type ProtocolFeeReceiver = CompanyReserve;
 	type MaximumCoinsEverAllocated = MaximumCoinsEverAllocated;
+	type MaxAllocs = MaxAllocs;
+	type MaxMembers = MaxMembers;
 	type ExistentialDeposit = <Runtime as pallet_balances::Config>::ExistentialDeposit;
+	type MintLimitOrigin = MoreThanHalfOfTechComm;
+	type MaxProofDepth = MaxProofDepth;
+	type ClaimPeriod = ClaimPeriod;
+	type Vesting = Vesting;
+	type QuorumOrigin = MoreThanHalfOfTechComm;
+	type QuorumVotingPeriod = QuorumVotingPeriod;
+	type EmissionCurve = ();
+	type BlockNumberProvider = RelayChainBlockNumberProvider<Runtime>;
+	type AccountingEpoch = AccountingEpoch;
+	type ProgramOrigin = MoreThanHalfOfTechComm;
 	type WeightInfo = pallet_allocations::weights::SubstrateWeight<Runtime>;
 }
 
@@ -17,13 +17,12 @@
  */
 
 use crate::{
-	constants, implementations::DealWithFees, version::VERSION, Balances, Call, CompanyReserve, Event, Origin,
-	PalletInfo, Runtime, SignedExtra, SignedPayload, System, UncheckedExtrinsic,
+	constants, implementations::DealWithFees, version::VERSION, Balances, Call, Event, Origin, PalletInfo, Runtime,
+	SignedExtra, SignedPayload, System, UncheckedExtrinsic,
 };
 use codec::Encode;
 use frame_support::{
 	parameter_types,
-	traits::Everything,
 	weights::{constants::RocksDbWeight, ConstantMultiplier, IdentityFee},
 };
 use frame_system::limits::BlockLength;
@@ -46,7 +45,7 @@ parameter_types! {
 }
 
 impl frame_system::Config for Runtime {
-	type BaseCallFilter = Everything;
+	type BaseCallFilter = pallet_emergency_shutdown::ShutdownFilter<Runtime>;
 	type BlockWeights = constants::RuntimeBlockWeights;
 	type BlockLength = RuntimeBlockLength;
 	type DbWeight = RocksDbWeight;
@@ -94,7 +93,7 @@ impl pallet_balances::Config for Runtime {
 	type MaxReserves = ();
 	type ReserveIdentifier = [u8; 8];
 	type Balance = Balance;
-	type DustRemoval = CompanyReserve;
+	type DustRemoval = pallet_reserve::DustInflow<Runtime, pallet_reserve::Instance1>;
 	type Event = Event;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = frame_system::Pallet<Runtime>;
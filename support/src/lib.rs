@@ -21,3 +21,11 @@
 pub trait WithAccountId<AccountId> {
 	fn account_id() -> AccountId;
 }
+
+/// Notified of the funds paid to it by a protocol, such as allocation fees.
+pub trait OnProtocolFee<Balance> {
+	fn on_protocol_fee(_amount: Balance) {}
+
+	/// Rewards which expired before being claimed were paid to it.
+	fn on_expired_rewards(_amount: Balance) {}
+}